@@ -0,0 +1,240 @@
+use std::path::Path;
+
+#[cfg(test)]
+mod tests;
+
+const RULES: &str = "rules.json";
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub(crate) struct Rules {
+    /// Maximum total cost increase over the baseline, in percent
+    pub max_cost_increase: Option<f32>,
+    /// Maximum ratio between the estimated rows and the baseline ones
+    pub max_rows_factor: Option<f32>,
+    /// Node types, optionally followed by `on <relation>`, that must not
+    /// appear in the plan
+    pub forbidden_nodes: Vec<String>,
+    /// Fail when the plan uses a node type absent from the baseline
+    pub new_node_types: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            max_cost_increase: Some(20.),
+            max_rows_factor: Some(10.),
+            forbidden_nodes: Vec::new(),
+            new_node_types: false,
+        }
+    }
+}
+
+/// Rules of a named query, overriding the global ones they set. A `null`
+/// value disables the rule.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct QueryRules {
+    #[serde(deserialize_with = "set")]
+    max_cost_increase: Option<Option<f32>>,
+    #[serde(deserialize_with = "set")]
+    max_rows_factor: Option<Option<f32>>,
+    forbidden_nodes: Option<Vec<String>>,
+    new_node_types: Option<bool>,
+}
+
+impl QueryRules {
+    fn merge(&self, rules: &Rules) -> Rules {
+        Rules {
+            max_cost_increase: self.max_cost_increase.unwrap_or(rules.max_cost_increase),
+            max_rows_factor: self.max_rows_factor.unwrap_or(rules.max_rows_factor),
+            forbidden_nodes: self
+                .forbidden_nodes
+                .clone()
+                .unwrap_or_else(|| rules.forbidden_nodes.clone()),
+            new_node_types: self.new_node_types.unwrap_or(rules.new_node_types),
+        }
+    }
+}
+
+/// Distinguishes a `null` field from a missing one.
+fn set<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Content of `rules.json`: the rules for every query, overridden by the
+/// ones in `queries` for the named queries.
+#[derive(Debug, Default, serde::Deserialize)]
+struct Config {
+    #[serde(flatten)]
+    rules: Rules,
+    #[serde(default)]
+    queries: std::collections::HashMap<String, QueryRules>,
+    /// Keys left by the rules and the queries, to reject typos
+    #[serde(flatten)]
+    unknown: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Config {
+    fn parse(json: &str) -> crate::Result<Self> {
+        let config: Self = serde_json::from_str(json)?;
+
+        match config.unknown.keys().next() {
+            Some(key) => Err(crate::Error::Rule(key.clone())),
+            None => Ok(config),
+        }
+    }
+
+    fn rules(&self, name: &str) -> Rules {
+        match self.queries.get(name) {
+            Some(rules) => rules.merge(&self.rules),
+            None => self.rules.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Outcome {
+    Checked(Vec<String>),
+    Updated,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Report {
+    queries: Vec<(String, Outcome)>,
+}
+
+impl Report {
+    pub fn is_success(&self) -> bool {
+        self.failures() == 0
+    }
+
+    fn failures(&self) -> usize {
+        self.queries
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, Outcome::Checked(x) if !x.is_empty()))
+            .count()
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, outcome) in &self.queries {
+            match outcome {
+                Outcome::Updated => writeln!(f, "{name}: updated")?,
+                Outcome::Checked(violations) if violations.is_empty() => writeln!(f, "{name}: ok")?,
+                Outcome::Checked(violations) => {
+                    writeln!(f, "{name}: {} violation(s)", violations.len())?;
+
+                    for violation in violations {
+                        writeln!(f, "  - {violation}")?;
+                    }
+                }
+            }
+        }
+
+        writeln!(
+            f,
+            "\n{} queries, {} failed",
+            self.queries.len(),
+            self.failures()
+        )
+    }
+}
+
+pub(crate) fn run(opt: &crate::Opt, check: &crate::CheckOpt) -> crate::Result<Report> {
+    let baseline = Path::new(&check.baseline);
+    let config = config(baseline)?;
+
+    let client = if check.plans.is_none() {
+        Some(crate::try_connect(opt)?)
+    } else {
+        None
+    };
+
+    let mut report = Report::default();
+
+    for name in names(baseline)? {
+        let path = match &check.plans {
+            Some(plans) => Path::new(plans).join(format!("{name}.json")),
+            None => baseline.join(format!("{name}.sql")),
+        };
+
+        if !path.exists() {
+            let violation = match &check.plans {
+                Some(_) => format!("no plan, {} doesn't exist", path.display()),
+                None => format!("no query, create {name}.sql to check it"),
+            };
+            report
+                .queries
+                .push((name, Outcome::Checked(vec![violation])));
+            continue;
+        }
+
+        let plan = match &client {
+            Some(client) => crate::explain(client, &std::fs::read_to_string(path)?, opt.analyse)?,
+            None => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+        };
+
+        let baseline_plan = baseline.join(format!("{name}.json"));
+
+        if check.update {
+            std::fs::write(&baseline_plan, serde_json::to_string_pretty(&plan)?)?;
+            report.queries.push((name, Outcome::Updated));
+            continue;
+        }
+
+        let violations = if baseline_plan.exists() {
+            let before: Vec<crate::Explain> =
+                serde_json::from_str(&std::fs::read_to_string(&baseline_plan)?)?;
+            let after: Vec<crate::Explain> = serde_json::from_value(plan)?;
+            let rules = config.rules(&name);
+
+            crate::graph::check(&before[0], &after[0], &rules)
+        } else {
+            vec!["no baseline plan, run with --update to create it".to_string()]
+        };
+
+        report.queries.push((name, Outcome::Checked(violations)));
+    }
+
+    Ok(report)
+}
+
+fn config(baseline: &Path) -> crate::Result<Config> {
+    let path = baseline.join(RULES);
+
+    if path.exists() {
+        Config::parse(&std::fs::read_to_string(path)?)
+    } else {
+        Ok(Config::default())
+    }
+}
+
+/// Names of the queries, from their SQL file or their baseline plan.
+fn names(baseline: &Path) -> crate::Result<std::collections::BTreeSet<String>> {
+    let mut names = std::collections::BTreeSet::new();
+
+    for entry in std::fs::read_dir(baseline)? {
+        let path = entry?.path();
+
+        if path.file_name().and_then(|x| x.to_str()) == Some(RULES) {
+            continue;
+        }
+
+        let extension = path.extension().and_then(|x| x.to_str());
+        if !matches!(extension, Some("sql" | "json")) {
+            continue;
+        }
+
+        if let Some(name) = path.file_stem().and_then(|x| x.to_str()) {
+            names.insert(name.to_string());
+        }
+    }
+
+    Ok(names)
+}
@@ -0,0 +1,161 @@
+use super::{Config, Rules};
+
+fn config() -> Config {
+    Config::parse(
+        r#"{
+            "max_cost_increase": 20,
+            "max_rows_factor": 10,
+            "forbidden_nodes": ["Seq Scan on orders"],
+            "new_node_types": false,
+            "queries": {
+                "report": { "max_cost_increase": 50 },
+                "export": { "max_rows_factor": null, "forbidden_nodes": [] }
+            }
+        }"#,
+    )
+    .unwrap()
+}
+
+fn explain(plan: &serde_json::Value) -> crate::Explain {
+    let mut explains: Vec<crate::Explain> = serde_json::from_value(plan.clone()).unwrap();
+
+    explains.remove(0)
+}
+
+fn plan() -> serde_json::Value {
+    serde_json::from_str(include_str!("../../examples/plan_2.json")).unwrap()
+}
+
+#[test]
+fn global_rules() {
+    let rules = config().rules("unknown");
+
+    assert_eq!(rules.max_cost_increase, Some(20.));
+    assert_eq!(rules.forbidden_nodes, ["Seq Scan on orders"]);
+}
+
+#[test]
+fn merged_rules() {
+    let config = config();
+
+    let report = config.rules("report");
+    assert_eq!(report.max_cost_increase, Some(50.));
+    assert_eq!(report.max_rows_factor, Some(10.));
+    assert_eq!(report.forbidden_nodes, ["Seq Scan on orders"]);
+
+    let export = config.rules("export");
+    assert_eq!(export.max_cost_increase, Some(20.));
+    assert_eq!(export.max_rows_factor, None);
+    assert!(export.forbidden_nodes.is_empty());
+}
+
+#[test]
+fn no_violation() {
+    let plan = explain(&plan());
+
+    assert!(crate::graph::check(&plan, &plan, &Rules::default()).is_empty());
+}
+
+#[test]
+fn cost_increase() {
+    let mut after = plan();
+    after[0]["Plan"]["Total Cost"] = 800.0.into();
+
+    let violations = crate::graph::check(&explain(&plan()), &explain(&after), &Rules::default());
+    assert_eq!(violations.len(), 1);
+    assert!(violations[0].starts_with("total cost 800.00"));
+
+    let rules = Rules {
+        max_cost_increase: Some(50.),
+        ..Rules::default()
+    };
+    assert!(crate::graph::check(&explain(&plan()), &explain(&after), &rules).is_empty());
+}
+
+#[test]
+fn rows_factor() {
+    let mut after = plan();
+    after[0]["Plan"]["Plan Rows"] = 10_000.into();
+
+    let violations = crate::graph::check(&explain(&plan()), &explain(&after), &Rules::default());
+    assert_eq!(violations.len(), 1);
+    assert!(violations[0].starts_with("estimated rows 10000"));
+}
+
+#[test]
+fn forbidden_nodes() {
+    let plan = explain(&plan());
+    let rules = Rules {
+        forbidden_nodes: vec!["Seq Scan on question".to_string(), "Hash Join".to_string()],
+        ..Rules::default()
+    };
+
+    let violations = crate::graph::check(&plan, &plan, &rules);
+    assert_eq!(violations.len(), 2);
+    assert!(violations[0].starts_with("forbidden Seq Scan on question"));
+    assert!(violations[1].starts_with("forbidden Hash Join"));
+}
+
+#[test]
+fn new_node_types() {
+    let mut after = plan();
+    after[0]["Plan"]["Node Type"] = "Materialize".into();
+
+    let rules = Rules {
+        new_node_types: true,
+        ..Rules::default()
+    };
+
+    let violations = crate::graph::check(&explain(&plan()), &explain(&after), &rules);
+    assert_eq!(
+        violations,
+        ["new node type Materialize, absent from the baseline"]
+    );
+}
+
+#[test]
+fn unknown_rules() {
+    assert!(matches!(
+        Config::parse(r#"{ "max_cost_increse": 5 }"#),
+        Err(crate::Error::Rule(key)) if key == "max_cost_increse"
+    ));
+    assert!(Config::parse(r#"{ "queries": { "report": { "max_cost_increse": 5 } } }"#).is_err());
+}
+
+#[test]
+fn missing_plan() {
+    use clap::Parser as _;
+
+    let dir = std::env::temp_dir().join(format!("explain-check-{}", std::process::id()));
+    let (baseline, plans) = (dir.join("baseline"), dir.join("plans"));
+    std::fs::create_dir_all(&baseline).unwrap();
+    std::fs::create_dir_all(&plans).unwrap();
+
+    let json = include_str!("../../examples/plan_2.json");
+    for name in ["found", "missing"] {
+        std::fs::write(baseline.join(format!("{name}.json")), json).unwrap();
+    }
+    std::fs::write(plans.join("found.json"), json).unwrap();
+
+    let opt = crate::Opt::parse_from([
+        "explain",
+        "check",
+        "--baseline",
+        baseline.to_str().unwrap(),
+        "--plans",
+        plans.to_str().unwrap(),
+    ]);
+    let Some(crate::Action::Check(check)) = &opt.action else {
+        unreachable!();
+    };
+
+    let report = super::run(&opt, check).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(report.failures(), 1);
+    assert!(
+        report
+            .to_string()
+            .starts_with("found: ok\nmissing: 1 violation(s)\n  - no plan")
+    );
+}
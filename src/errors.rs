@@ -4,12 +4,22 @@ pub type Result<T = ()> = std::result::Result<T, Error>;
 pub enum Error {
     #[error("{0}")]
     Convert(#[from] std::num::ParseIntError),
+    #[error("{0} is only supported by the dot format")]
+    DotOnly(&'static str),
     #[error("{0}")]
     Fmt(#[from] std::fmt::Error),
+    #[error("Unable to draw the flamegraph: {0}")]
+    Flamegraph(String),
+    #[error("No node with the id or relation {0}")]
+    Focus(String),
     #[error("{0}")]
     Io(#[from] std::io::Error),
+    #[error("--lint only supports the default --format")]
+    LintFormat,
     #[error("{0}")]
     Elephantry(#[from] elephantry::Error),
+    #[error("Unknown rule {0} in rules.json")]
+    Rule(String),
     #[error("{0}")]
     Serde(#[from] serde_json::Error),
 }
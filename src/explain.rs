@@ -7,7 +7,7 @@ pub(crate) struct Explain {
     #[serde(rename = "Total Runtime", default)]
     pub total_runtime: Option<f32>,
     #[serde(rename = "Planning Time", default)]
-    pub _planning_time: Option<f32>,
+    pub planning_time: Option<f32>,
     #[serde(rename = "Triggers", default)]
     pub _triggers: Vec<Trigger>,
 }
@@ -17,25 +17,27 @@ pub(crate) struct Plan {
     #[serde(rename = "Actual Loops", default)]
     pub actual_loops: Option<usize>,
     #[serde(rename = "Actual Startup Time", default)]
-    pub _actual_startup_time: Option<f32>,
+    pub actual_startup_time: Option<f32>,
     #[serde(rename = "Actual Total Time", default)]
     pub actual_total_time: Option<f32>,
     #[serde(flatten)]
     pub node: Node,
+    #[serde(flatten)]
+    pub attributes: serde_json::Map<String, serde_json::Value>,
     #[serde(rename = "Output", default)]
-    pub _output: Vec<String>,
+    pub output: Vec<String>,
     #[serde(rename = "Parallel Aware", default)]
-    pub _parallel_aware: bool,
+    pub parallel_aware: bool,
     #[serde(rename = "Parent Relationship")]
     pub parent_relationship: Option<String>,
     #[serde(rename = "Plan Rows")]
     pub rows: u32,
     #[serde(rename = "Plan Width")]
-    pub _width: u32,
+    pub width: u32,
     #[serde(rename = "Plans", default)]
     pub plans: Vec<Plan>,
     #[serde(rename = "Startup Cost")]
-    pub _startup_cost: f32,
+    pub startup_cost: f32,
     #[serde(rename = "Subplan Name")]
     pub subplan: Option<String>,
     #[serde(rename = "Total Cost")]
@@ -55,7 +57,10 @@ pub(crate) enum Node {
         #[serde(rename = "Strategy")]
         strategy: Strategy,
     },
-    Append {},
+    Append {
+        #[serde(rename = "Subplans Removed", default)]
+        subplans_removed: usize,
+    },
     BitmapAnd {},
     #[serde(rename = "Bitmap Index Scan")]
     BitmapIndexScan {},
@@ -69,13 +74,35 @@ pub(crate) enum Node {
     },
     CustomScan {},
     #[serde(rename = "Foreign Scan")]
-    ForeignScan {},
+    ForeignScan {
+        #[serde(rename = "Operation", default)]
+        operation: Option<Operation>,
+        #[serde(flatten)]
+        relation: Option<Relation>,
+        /// Relations scanned remotely, when a join or an aggregate is pushed
+        /// down
+        #[serde(rename = "Relations", default)]
+        relations: Option<String>,
+        #[serde(rename = "Remote SQL", default)]
+        remote_sql: Option<String>,
+    },
     #[serde(rename = "Function Scan")]
-    FunctionScan {},
+    FunctionScan {
+        #[serde(rename = "Alias", default)]
+        alias: String,
+        /// Call expression, only in verbose mode
+        #[serde(rename = "Function Call", default)]
+        call: Option<String>,
+        #[serde(rename = "Function Name", default)]
+        name: String,
+    },
     Gather {},
     #[serde(rename = "Gather Merge")]
     GatherMerge {},
-    Group {},
+    Group {
+        #[serde(rename = "Group Key", default)]
+        keys: Vec<String>,
+    },
     Hash {},
     #[serde(rename = "Hash Join")]
     HashJoin {
@@ -100,9 +127,15 @@ pub(crate) enum Node {
     Limit {},
     LockRows {},
     Materialize {},
-    Memoize {},
+    Memoize {
+        #[serde(flatten)]
+        cache: Cache,
+    },
     #[serde(rename = "Merge Append")]
-    MergeAppend {},
+    MergeAppend {
+        #[serde(rename = "Subplans Removed", default)]
+        subplans_removed: usize,
+    },
     #[serde(rename = "Merge Join")]
     MergeJoin {
         #[serde(rename = "Join Type")]
@@ -135,6 +168,9 @@ pub(crate) enum Node {
         relation: Relation,
     },
     SetOp {
+        /// Intersect or Except, with All
+        #[serde(rename = "Command", default)]
+        command: String,
         #[serde(rename = "Strategy")]
         strategy: Strategy,
     },
@@ -143,17 +179,40 @@ pub(crate) enum Node {
         keys: Vec<String>,
     },
     #[serde(rename = "Subquery Scan")]
-    SubqueryScan {},
+    SubqueryScan {
+        #[serde(rename = "Alias", default)]
+        alias: String,
+    },
     #[serde(rename = "Table Function Scan")]
-    TableFunctionScan {},
+    TableFunctionScan {
+        #[serde(rename = "Alias", default)]
+        alias: String,
+        /// Call expression, only in verbose mode
+        #[serde(rename = "Table Function Call", default)]
+        call: Option<String>,
+        #[serde(rename = "Table Function Name", default)]
+        name: String,
+    },
     #[serde(rename = "Tid Scan")]
     TidScan {},
     Unique {},
-    #[serde(rename = "Value Scan")]
-    ValueScan {},
-    WindowAgg {},
+    #[serde(rename = "Values Scan", alias = "Value Scan")]
+    ValueScan {
+        #[serde(rename = "Alias", default)]
+        alias: String,
+    },
+    WindowAgg {
+        /// Window definition, since PostgreSQL 18
+        #[serde(rename = "Window", default)]
+        window: Option<String>,
+        #[serde(rename = "Run Condition", default)]
+        run_condition: Option<String>,
+    },
     #[serde(rename = "WorkTable Scan")]
-    WorkTableScan {},
+    WorkTableScan {
+        #[serde(rename = "CTE Name")]
+        name: String,
+    },
 }
 
 impl std::fmt::Display for Node {
@@ -193,10 +252,10 @@ impl std::fmt::Display for Node {
             Self::SetOp { .. } => "SetOp",
             Self::Sort { .. } => "Sort",
             Self::SubqueryScan { .. } => "Subquery Scan",
-            Self::TableFunctionScan { .. } => "Table Funciton Scan",
+            Self::TableFunctionScan { .. } => "Table Function Scan",
             Self::TidScan { .. } => "Tid Scan",
             Self::Unique { .. } => "Unique",
-            Self::ValueScan { .. } => "Value Scan",
+            Self::ValueScan { .. } => "Values Scan",
             Self::WindowAgg { .. } => "WindowAgg",
             Self::WorkTableScan { .. } => "WorkTable Scan",
         };
@@ -238,6 +297,44 @@ pub(crate) enum JoinType {
     Semi,
 }
 
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Delete => "delete",
+            Self::Insert => "insert",
+            Self::Select => "select",
+            Self::Update => "update",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+impl std::fmt::Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Hashed => "hashed",
+            Self::Mixed => "mixed",
+            Self::Plain => "plain",
+            Self::Sorted => "sorted",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+impl std::fmt::Display for PartialMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Finalize => "finalize",
+            Self::Partial => "partial",
+            Self::Simple => "simple",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
 impl std::fmt::Display for JoinType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
@@ -273,6 +370,37 @@ impl std::fmt::Display for Relation {
     }
 }
 
+#[derive(Clone, Debug, serde::Deserialize, PartialEq)]
+pub(crate) struct Cache {
+    #[serde(rename = "Cache Key")]
+    pub key: String,
+    #[serde(rename = "Cache Mode", default)]
+    pub mode: Option<String>,
+    #[serde(rename = "Cache Hits", default)]
+    pub hits: Option<u64>,
+    #[serde(rename = "Cache Misses", default)]
+    pub misses: Option<u64>,
+    #[serde(rename = "Cache Evictions", default)]
+    pub evictions: Option<u64>,
+    #[serde(rename = "Cache Overflows", default)]
+    pub overflows: Option<u64>,
+    #[serde(rename = "Peak Memory Usage", default)]
+    pub peak_memory: Option<u64>,
+}
+
+impl Cache {
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let hits = self.hits?;
+        let lookups = hits + self.misses?;
+
+        if lookups == 0 {
+            None
+        } else {
+            Some(hits as f64 / lookups as f64)
+        }
+    }
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub(crate) struct Trigger {
     #[serde(rename = "Trigger Name")]
@@ -285,16 +413,16 @@ pub(crate) struct Trigger {
     _calls: u32,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Deserialize)]
 pub(crate) struct Worker {
     #[serde(rename = "Worker Number")]
-    _number: usize,
+    pub number: usize,
     #[serde(rename = "Actual Startup Time")]
-    _actual_startup_time: f32,
+    pub actual_startup_time: f32,
     #[serde(rename = "Actual Total Time")]
-    _actual_total_time: f32,
+    pub actual_total_time: f32,
     #[serde(rename = "Actual Rows")]
-    _actual_rows: usize,
+    pub actual_rows: usize,
     #[serde(rename = "Actual Loops")]
-    _actual_loops: usize,
+    pub actual_loops: usize,
 }
@@ -1,5 +1,180 @@
-pub(crate) fn dot(explain: &crate::Explain) -> String {
-    Graph::from(explain).render()
+mod check;
+mod csv;
+mod cytoscape;
+mod diff;
+mod drawio;
+mod folded;
+mod graphml;
+mod json;
+mod lint;
+mod markdown;
+mod mermaid;
+mod plantuml;
+#[cfg(test)]
+mod tests;
+mod timeline;
+mod trace;
+
+/// Ratio between estimated and actual rows considered as a misestimate.
+const MISESTIMATE: f64 = 10.;
+/// Rows read above which a relation is considered big.
+const BIG_RELATION: f64 = 10_000.;
+/// Row width above which a node is considered as dragging wide rows, in
+/// bytes.
+const WIDE_ROW: u32 = 1_000;
+/// Relative deviation from the mean above which a parallel worker is
+/// considered imbalanced.
+const IMBALANCE: f64 = 0.2;
+/// Precision of the times reported by PostgreSQL, in ms.
+const TIME_PRECISION: f32 = 0.001;
+/// Maximum number of lines of a scan filter.
+const FILTER_LINES: usize = 3;
+/// Maximum number of lines of the remote SQL of a foreign scan.
+const REMOTE_SQL_LINES: usize = 8;
+/// Maximum number of lines of the output expressions.
+const OUTPUT_LINES: usize = 4;
+/// Maximum number of parallel lines drawing an edge.
+const MAX_FLOW_WIDTH: usize = 4;
+
+/// How to draw the graph.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Options {
+    /// Draw the identical sibling subtrees once
+    pub collapse: bool,
+    /// Only draw the critical path
+    pub critical_path: bool,
+    /// Only draw the subtrees of the nodes with this id or relation, and
+    /// their ancestors
+    pub focus: Option<String>,
+    /// Replace the deeper subtrees by a summary
+    pub max_depth: Option<usize>,
+    /// Hide the nodes taking less than this share of the execution time
+    pub min_time_percent: Option<f32>,
+    /// List the output expressions of the nodes
+    pub show_output: bool,
+}
+
+pub(crate) fn dot(explain: &crate::Explain, options: &Options) -> crate::Result<String> {
+    let mut graph = Graph::from(explain);
+    graph.options = options.clone();
+    if options.collapse {
+        graph.group_siblings();
+    }
+    graph.filter()?;
+
+    Ok(graph.render())
+}
+
+pub(crate) fn mermaid(explain: &crate::Explain) -> String {
+    mermaid::render(&Graph::from(explain))
+}
+
+pub(crate) fn folded(explain: &crate::Explain) -> String {
+    folded::render(&Graph::from(explain))
+}
+
+pub(crate) fn flamegraph(explain: &crate::Explain) -> crate::Result<String> {
+    folded::flamegraph(&Graph::from(explain))
+}
+
+pub(crate) fn speedscope(explain: &crate::Explain) -> String {
+    trace::speedscope(&Graph::from(explain))
+}
+
+pub(crate) fn chrome(explain: &crate::Explain) -> String {
+    trace::chrome(&Graph::from(explain))
+}
+
+pub(crate) fn timeline(explain: &crate::Explain) -> String {
+    timeline::render(&Graph::from(explain))
+}
+
+pub(crate) fn json(explain: &crate::Explain) -> String {
+    json::render(&Graph::from(explain))
+}
+
+pub(crate) fn csv(explain: &crate::Explain) -> String {
+    csv::render(&Graph::from(explain), b',')
+}
+
+pub(crate) fn tsv(explain: &crate::Explain) -> String {
+    csv::render(&Graph::from(explain), b'\t')
+}
+
+pub(crate) fn markdown(explain: &crate::Explain) -> String {
+    markdown::render(&Graph::from(explain))
+}
+
+pub(crate) fn plantuml(explain: &crate::Explain) -> String {
+    plantuml::render(&Graph::from(explain))
+}
+
+pub(crate) fn drawio(explain: &crate::Explain) -> String {
+    drawio::render(&Graph::from(explain))
+}
+
+pub(crate) fn graphml(explain: &crate::Explain) -> String {
+    graphml::render(&Graph::from(explain))
+}
+
+pub(crate) fn cytoscape(explain: &crate::Explain) -> String {
+    cytoscape::render(&Graph::from(explain))
+}
+
+pub(crate) fn lint(explain: &crate::Explain) -> String {
+    lint::report(&Graph::from(explain))
+}
+
+pub(crate) fn check(
+    baseline: &crate::Explain,
+    plan: &crate::Explain,
+    rules: &crate::check::Rules,
+) -> Vec<String> {
+    check::violations(&Graph::from(baseline), &Graph::from(plan), rules)
+}
+
+pub(crate) fn diff_dot(before: &crate::Explain, after: &crate::Explain) -> String {
+    diff::Diff::from(Graph::from(before), Graph::from(after)).render()
+}
+
+pub(crate) fn diff_markdown(before: &crate::Explain, after: &crate::Explain) -> String {
+    diff::Diff::from(Graph::from(before), Graph::from(after)).markdown()
+}
+
+/// Splits a text in lines of at most `width` characters on spaces, and keeps
+/// the `max_lines` first ones.
+fn wrap(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines = Vec::<String>::new();
+
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + word.chars().count() < width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        lines[max_lines - 1].push_str(" …");
+    }
+
+    lines
+}
+
+/// Shortest decimal form of a value, rather than its `f64` widening like
+/// 0.1340000033378601.
+fn decimal(value: f32) -> f64 {
+    value.to_string().parse().unwrap_or(f64::from(value))
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
 type Su<'a> = String;
@@ -13,6 +188,18 @@ struct Graph {
     current_id: usize,
     max_cost: f32,
     execution_time: Option<f32>,
+    planning_time: Option<f32>,
+    critical_path: Vec<Nd>,
+    /// Edges from the nodes using a parameter or a CTE to the subplan
+    /// producing it
+    dependencies: Vec<(usize, usize)>,
+    /// Loop-back edges from the worktable scans to their recursive union
+    recursions: Vec<(usize, usize)>,
+    options: Options,
+    visible: Vec<bool>,
+    /// Edges from the nodes to their closest visible ancestor, when their
+    /// parent is hidden
+    shortcuts: Vec<(usize, usize)>,
 }
 
 impl Graph {
@@ -27,7 +214,12 @@ impl Graph {
             .execution_time
             .or(explain.total_runtime)
             .or(explain.plan.actual_total_time);
+        graph.planning_time = explain.planning_time;
         graph.plan(None, &explain.plan);
+        graph.dependencies = graph.dependencies();
+        graph.recursions = graph.recursions();
+        graph.exclusive_times();
+        graph.critical_path = graph.critical_path();
 
         graph
     }
@@ -40,6 +232,14 @@ impl Graph {
         if node.subplan.is_none() {
             node.subplan = root.and_then(|x| x.subplan.clone());
         }
+        node.participants = match root {
+            Some(root) if matches!(root.ty.as_str(), "Gather" | "Gather Merge") => {
+                root.launched_participants()
+            }
+            Some(root) => root.participants,
+            None => 1,
+        };
+        node.inclusive_time = node.wall_time();
 
         if node.cost > self.max_cost {
             self.max_cost = node.cost;
@@ -67,6 +267,705 @@ impl Graph {
         self.nodes.get(n)
     }
 
+    fn children(&self, n: Nd) -> impl Iterator<Item = Nd> + '_ {
+        self.edges
+            .iter()
+            .filter(move |(source, _)| *source == n)
+            .map(|(_, target)| *target)
+    }
+
+    fn parent(&self, n: Nd) -> Option<Nd> {
+        self.edges
+            .iter()
+            .find(|(_, target)| *target == n)
+            .map(|(source, _)| *source)
+    }
+
+    fn depth(&self, n: Nd) -> usize {
+        self.parent(n).map_or(0, |parent| self.depth(parent) + 1)
+    }
+
+    fn is_ancestor(&self, ancestor: Nd, n: Nd) -> bool {
+        n == ancestor
+            || self
+                .parent(n)
+                .is_some_and(|parent| self.is_ancestor(ancestor, parent))
+    }
+
+    fn dependencies(&self) -> Vec<(usize, usize)> {
+        let mut dependencies = Vec::new();
+
+        for (root, node) in self.nodes.iter().enumerate() {
+            let Some(name) = &node.subplan else {
+                continue;
+            };
+            if self
+                .parent(root)
+                .and_then(|x| self.nodes[x].subplan.as_ref())
+                == Some(name)
+            {
+                continue;
+            }
+
+            for (n, other) in self.nodes.iter().enumerate() {
+                if self.is_ancestor(root, n) || self.edges.contains(&(n, root)) {
+                    continue;
+                }
+
+                if other.references(name) {
+                    dependencies.push((n, root));
+                }
+            }
+        }
+
+        dependencies
+    }
+
+    fn recursions(&self) -> Vec<(usize, usize)> {
+        let mut recursions = Vec::new();
+
+        for (n, node) in self.nodes.iter().enumerate() {
+            if node.ty != "WorkTable Scan" {
+                continue;
+            }
+
+            let mut ancestor = self.parent(n);
+            while let Some(parent) = ancestor {
+                if self.nodes[parent].ty == "Recursive Union" {
+                    recursions.push((n, parent));
+                    break;
+                }
+                ancestor = self.parent(parent);
+            }
+        }
+
+        recursions
+    }
+
+    /// Nodes whose time includes the execution of a subplan: the ones using
+    /// its result, or its parent.
+    fn consumers(&self, n: Nd) -> Vec<Nd> {
+        let parent = self.parent(n);
+        let references = self
+            .dependencies
+            .iter()
+            .filter(|(_, target)| *target == n)
+            .map(|(source, _)| *source)
+            .collect::<Vec<_>>();
+        let used_by_parent = parent.is_some_and(|parent| {
+            self.nodes[n]
+                .subplan
+                .as_ref()
+                .is_some_and(|name| self.nodes[parent].references(name))
+        });
+
+        if references.is_empty() || used_by_parent {
+            parent.into_iter().collect()
+        } else {
+            references
+        }
+    }
+
+    /// Computes the exclusive time of each node: its inclusive time minus the
+    /// time of the children and subplans it consumes.
+    ///
+    /// A subplan consumed by several nodes, like a CTE scanned twice, is
+    /// shared between them in proportion of their inclusive time, which is an
+    /// estimation.
+    fn exclusive_times(&mut self) {
+        let mut times = self
+            .nodes
+            .iter()
+            .map(|node| {
+                (
+                    node.inclusive_time,
+                    Accuracy::Exact,
+                    node.loops.unwrap_or(1),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for n in 1..self.nodes.len() {
+            let inclusive = self.nodes[n].inclusive_time.unwrap_or_default();
+            let consumers = self.consumers(n);
+            let total = consumers
+                .iter()
+                .map(|x| self.nodes[*x].inclusive_time.unwrap_or_default())
+                .sum::<f32>();
+
+            for consumer in &consumers {
+                let (time, accuracy, loops) = &mut times[*consumer];
+                let Some(time) = time else {
+                    continue;
+                };
+
+                if consumers.len() == 1 {
+                    *time -= inclusive;
+                } else if total > 0. {
+                    *time -= inclusive * self.nodes[*consumer].inclusive_time.unwrap_or_default()
+                        / total;
+                    *accuracy = Accuracy::Estimated;
+                }
+                *loops += self.nodes[n].loops.unwrap_or(1);
+            }
+        }
+
+        for (node, (time, mut accuracy, loops)) in self.nodes.iter_mut().zip(times) {
+            let Some(mut time) = time else {
+                continue;
+            };
+
+            // Times are averages per loop rounded by PostgreSQL: the error is
+            // multiplied by the loops of the node and its children.
+            let error = TIME_PRECISION / 2. * loops as f32;
+            let parallel_append = node.parallel_aware && node.ty == "Append";
+
+            if parallel_append || error > (time.abs() * 0.1).max(0.1) {
+                accuracy = Accuracy::Estimated;
+            }
+            if time < 0. {
+                time = 0.;
+                accuracy = Accuracy::Clamped;
+            }
+
+            node.time = Some(time);
+            node.accuracy = accuracy;
+        }
+    }
+
+    fn is_dependency(&self, e: Ed<'_>) -> bool {
+        self.dependencies.contains(e)
+    }
+
+    fn is_recursion(&self, e: Ed<'_>) -> bool {
+        self.recursions.contains(e)
+    }
+
+    fn descendants(&self, n: Nd) -> usize {
+        self.children(n)
+            .map(|child| self.descendants(child) + 1)
+            .sum()
+    }
+
+    /// Root-to-leaf path with the highest cumulative exclusive time, or cost
+    /// when the plan isn't analysed.
+    fn critical_path(&self) -> Vec<Nd> {
+        let analysed = self.nodes.first().is_some_and(|x| x.time.is_some());
+        let weight = |node: &Node| {
+            if analysed {
+                node.time.unwrap_or_default().max(0.)
+            } else {
+                node.cost
+            }
+        };
+
+        // Nodes are numbered in pre-order: children come after their parent.
+        let mut cumulative = vec![0_f32; self.nodes.len()];
+        let mut next = vec![None; self.nodes.len()];
+        for n in (0..self.nodes.len()).rev() {
+            let child = self
+                .children(n)
+                .max_by(|a, b| cumulative[*a].total_cmp(&cumulative[*b]));
+
+            cumulative[n] = weight(&self.nodes[n]) + child.map_or(0., |x| cumulative[x]);
+            next[n] = child;
+        }
+
+        let mut path = Vec::new();
+        let mut n = (!self.nodes.is_empty()).then_some(0);
+        while let Some(current) = n {
+            path.push(current);
+            n = next[current];
+        }
+
+        path
+    }
+
+    /// Rows flowing out of a node: the estimated rows, and the actual ones
+    /// for all loops when analysed.
+    fn flow(&self, n: Nd) -> (f64, Option<f64>) {
+        let node = &self.nodes[n];
+
+        std::iter::once(n)
+            .chain(node.group.iter().copied())
+            .map(|x| {
+                let node = &self.nodes[x];
+                let actual = node
+                    .actual_rows()
+                    .map(|rows| rows * node.loops.unwrap_or(1) as f64);
+
+                (f64::from(node.rows), actual)
+            })
+            .reduce(|(estimated, actual), (other_estimated, other_actual)| {
+                (
+                    estimated + other_estimated,
+                    actual.zip(other_actual).map(|(a, b)| a + b),
+                )
+            })
+            .unwrap()
+    }
+
+    fn flow_label(&self, n: Nd) -> String {
+        let (estimated, actual) = self.flow(n);
+        let rows = actual.unwrap_or(estimated);
+        let unit = if rows == 1. { "row" } else { "rows" };
+
+        match actual {
+            Some(actual) => format!("{actual} {unit} (est. {estimated})"),
+            None => format!("{estimated} {unit}"),
+        }
+    }
+
+    /// Number of parallel lines drawing an edge, growing with the order of
+    /// magnitude of its rows.
+    fn flow_width(&self, n: Nd) -> usize {
+        let (estimated, actual) = self.flow(n);
+        let rows = actual.unwrap_or(estimated).max(1.);
+
+        (1 + rows.log10() as usize / 2).min(MAX_FLOW_WIDTH)
+    }
+
+    fn on_critical_path(&self, n: Nd) -> bool {
+        std::iter::once(&n)
+            .chain(&self.nodes[n].group)
+            .any(|x| self.critical_path.contains(x))
+    }
+
+    fn is_visible(&self, n: Nd) -> bool {
+        self.visible.get(n).copied().unwrap_or(true)
+    }
+
+    /// Is this node drawn as a summary of its subtree, below the maximum
+    /// depth?
+    fn is_stub(&self, n: Nd) -> bool {
+        self.options
+            .max_depth
+            .is_some_and(|max_depth| self.depth(n) == max_depth + 1)
+    }
+
+    /// Computes the visible nodes from the options, and the edges keeping
+    /// the tree connected.
+    fn filter(&mut self) -> crate::Result {
+        let focused = match &self.options.focus {
+            Some(focus) => {
+                let mut matches = (0..self.nodes.len())
+                    .filter(|n| self.nodes[*n].is(focus))
+                    .map(|n| self.representative(n))
+                    .collect::<Vec<_>>();
+                matches.sort_unstable();
+                matches.dedup();
+
+                if matches.is_empty() {
+                    return Err(crate::Error::Focus(focus.clone()));
+                }
+
+                (0..self.nodes.len())
+                    .map(|n| {
+                        matches
+                            .iter()
+                            .any(|x| self.is_ancestor(*x, n) || self.is_ancestor(n, *x))
+                    })
+                    .collect()
+            }
+            None => vec![true; self.nodes.len()],
+        };
+
+        self.visible = (0..self.nodes.len())
+            .map(|n| {
+                let node = &self.nodes[n];
+                let depth = self.depth(n);
+                let fast = match (
+                    self.options.min_time_percent,
+                    self.merged(n).time,
+                    self.execution_time,
+                ) {
+                    (Some(min), Some(time), Some(total)) => n != 0 && time / total * 100. < min,
+                    _ => false,
+                };
+
+                !node.hidden
+                    && focused[n]
+                    && (!self.options.critical_path || self.on_critical_path(n))
+                    && self.options.max_depth.is_none_or(|max| depth <= max + 1)
+                    && (!fast || self.is_stub(n))
+            })
+            .collect();
+
+        self.shortcuts = (0..self.nodes.len())
+            .filter(|n| self.visible[*n])
+            .filter_map(|n| {
+                let parent = self.parent(n)?;
+                if self.visible[parent] {
+                    return None;
+                }
+
+                let mut ancestor = self.parent(parent);
+                while let Some(x) = ancestor
+                    && !self.visible[x]
+                {
+                    ancestor = self.parent(x);
+                }
+
+                ancestor.map(|ancestor| (ancestor, n))
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    /// Nodes of a subtree, in pre-order.
+    fn subtree(&self, n: Nd) -> Vec<Nd> {
+        let mut nodes = vec![n];
+        for child in self.children(n) {
+            nodes.extend(self.subtree(child));
+        }
+
+        nodes
+    }
+
+    /// Node types of a subtree, and optionally their relations, to compare
+    /// the structure of siblings.
+    fn shape(&self, n: Nd, relations: bool) -> String {
+        let node = &self.nodes[n];
+        let children = self
+            .children(n)
+            .map(|child| self.shape(child, relations))
+            .collect::<Vec<_>>();
+
+        let relation = if relations {
+            let index = node.attribute("Index Name").and_then(|x| x.as_str());
+
+            node.relation()
+                .or_else(|| node.attribute("CTE Name")?.as_str().map(String::from))
+                .into_iter()
+                .chain(index.map(String::from))
+                .collect::<Vec<_>>()
+                .join(" using ")
+        } else {
+            String::new()
+        };
+
+        format!("{}[{relation}]({})", node.ty, children.join(","))
+    }
+
+    /// Groups the identical sibling subtrees, and the partitions scanned the
+    /// same way by an Append, to draw each group once.
+    fn group_siblings(&mut self) {
+        #[derive(PartialEq)]
+        enum Sibling {
+            Partition {
+                executed: bool,
+                alias: Option<String>,
+                index: Option<String>,
+                shape: String,
+            },
+            Identical {
+                relationship: Option<String>,
+                subplan: Option<String>,
+                shape: String,
+            },
+        }
+
+        for n in 0..self.nodes.len() {
+            // Each member of a bitmap combines a different condition.
+            if self.nodes[n].hidden || matches!(self.nodes[n].ty.as_str(), "BitmapAnd" | "BitmapOr")
+            {
+                continue;
+            }
+
+            let append = matches!(self.nodes[n].ty.as_str(), "Append" | "Merge Append");
+            let mut groups = Vec::<(Sibling, Vec<Nd>)>::new();
+
+            for child in self.children(n) {
+                let node = &self.nodes[child];
+
+                let key = if append && node.relation().is_some() {
+                    // Partition indexes are named after their partition.
+                    let index = node
+                        .attribute("Index Name")
+                        .and_then(|x| x.as_str())
+                        .map(|x| {
+                            let relation = node.attribute("Relation Name").and_then(|x| x.as_str());
+
+                            x.replace(relation.unwrap_or_default(), "")
+                        });
+
+                    Sibling::Partition {
+                        executed: node.executed,
+                        alias: node.table_alias(),
+                        index,
+                        shape: self.shape(child, false),
+                    }
+                } else {
+                    Sibling::Identical {
+                        relationship: node.parent_relationship.clone(),
+                        subplan: node.subplan.clone(),
+                        shape: self.shape(child, true),
+                    }
+                };
+
+                match groups.iter_mut().find(|(x, _)| *x == key) {
+                    Some((_, members)) => members.push(child),
+                    None => groups.push((key, vec![child])),
+                }
+            }
+
+            for (key, members) in groups {
+                if members.len() < 2 {
+                    continue;
+                }
+
+                let summary = match key {
+                    Sibling::Partition { executed: true, .. } => {
+                        format!("×{} partitions scanned", members.len())
+                    }
+                    Sibling::Partition {
+                        executed: false, ..
+                    } => format!("×{} partitions never executed", members.len()),
+                    Sibling::Identical { .. } => format!("×{}", members.len()),
+                };
+                self.merge(&members, summary);
+            }
+        }
+    }
+
+    /// Draws identical sibling subtrees as the first one.
+    fn merge(&mut self, members: &[Nd], summary: String) {
+        let subtrees = members
+            .iter()
+            .map(|member| self.subtree(*member))
+            .collect::<Vec<_>>();
+
+        for (k, n) in subtrees[0].iter().enumerate() {
+            for subtree in &subtrees[1..] {
+                let counterpart = subtree[k];
+                let group = std::mem::take(&mut self.nodes[counterpart].group);
+
+                self.nodes[counterpart].hidden = true;
+                self.nodes[*n].group.push(counterpart);
+                self.nodes[*n].group.extend(group);
+            }
+        }
+
+        self.nodes[members[0]].summary = Some(summary);
+    }
+
+    /// The node drawn for `n`: the first one of its group.
+    fn representative(&self, n: Nd) -> Nd {
+        self.nodes
+            .iter()
+            .position(|x| x.group.contains(&n))
+            .unwrap_or(n)
+    }
+
+    /// The node with the values of the nodes it stands for added up.
+    fn merged(&self, n: Nd) -> std::borrow::Cow<'_, Node> {
+        let node = &self.nodes[n];
+
+        if node.group.is_empty() {
+            return std::borrow::Cow::Borrowed(node);
+        }
+
+        let add = |a: Option<f32>, b: Option<f32>| match (a, b) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+
+        let mut merged = node.clone();
+        for other in node.group.iter().map(|x| &self.nodes[*x]) {
+            merged.cost += other.cost;
+            merged.total_cost += other.total_cost;
+            merged.rows += other.rows;
+            merged.executed |= other.executed;
+            merged.time = add(merged.time, other.time);
+            merged.inclusive_time = add(merged.inclusive_time, other.inclusive_time);
+            if other.accuracy != Accuracy::Exact {
+                merged.accuracy = other.accuracy;
+            }
+        }
+
+        std::borrow::Cow::Owned(merged)
+    }
+
+    fn group_relations(&self, n: Nd) -> String {
+        let relations = std::iter::once(&n)
+            .chain(&self.nodes[n].group)
+            .filter_map(|x| self.nodes[*x].relation())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if relations.len() > 80 {
+            format!(
+                "{}…",
+                relations.split_at(relations.floor_char_boundary(80)).0
+            )
+        } else {
+            relations
+        }
+    }
+
+    fn worker_numbers(&self) -> Vec<usize> {
+        let mut numbers = self
+            .nodes
+            .iter()
+            .flat_map(|node| node.workers.iter().map(|worker| worker.number))
+            .collect::<Vec<_>>();
+
+        numbers.sort_unstable();
+        numbers.dedup();
+
+        numbers
+    }
+
+    fn cost_percent(&self, node: &Node) -> f32 {
+        node.cost / self.max_cost
+    }
+
+    fn time_percent(&self, time: f32) -> f32 {
+        (time / self.execution_time.unwrap() * 100.).round().trunc()
+    }
+
+    fn time_label(&self, node: &Node) -> Option<String> {
+        let time = node.time?;
+
+        let label = if !node.executed {
+            "Never executed".to_string()
+        } else if time < 1. {
+            format!("{}< 1 ms | {} %", node.accuracy, self.time_percent(time))
+        } else {
+            format!(
+                "{}{time:.2} ms | {} %",
+                node.accuracy,
+                self.time_percent(time)
+            )
+        };
+
+        Some(label)
+    }
+
+    /// Plain text label lines, for the formats without rich labels.
+    fn label_lines(&self, node: &Node) -> Vec<String> {
+        let mut lines = vec![match self.time_label(node) {
+            Some(time) => format!("{} | {time}", node.ty),
+            None => node.ty.clone(),
+        }];
+
+        if !node.info.is_empty() {
+            lines.push(node.info.clone());
+        }
+        lines.extend(node.details.iter().cloned());
+        if !node.workers.is_empty() {
+            lines.push(format!("Workers: {}", node.workers.len()));
+        }
+        lines.push(format!("Cost: {:.02}", node.cost));
+        lines.push(format!("Rows: {}", node.rows));
+
+        lines
+    }
+
+    /// Computed metrics of a node, with their GraphML type. Missing values are
+    /// `null`.
+    fn metrics(&self, n: Nd) -> Vec<(&'static str, &'static str, serde_json::Value)> {
+        use serde_json::json;
+
+        let node = &self.nodes[n];
+
+        vec![
+            ("id", "int", json!(node.id)),
+            ("parent_id", "int", json!(self.parent(n))),
+            ("depth", "int", json!(self.depth(n))),
+            ("node_type", "string", json!(node.ty)),
+            ("info", "string", json!(node.info)),
+            ("relation", "string", json!(node.relation())),
+            ("subplan", "string", json!(node.subplan)),
+            (
+                "parent_relationship",
+                "string",
+                json!(node.parent_relationship),
+            ),
+            ("startup_cost", "double", json!(decimal(node.startup_cost))),
+            ("total_cost", "double", json!(decimal(node.total_cost))),
+            ("exclusive_cost", "double", json!(decimal(node.cost))),
+            (
+                "cost_percent",
+                "double",
+                json!(decimal(self.cost_percent(node) * 100.)),
+            ),
+            ("plan_rows", "long", json!(node.rows)),
+            ("plan_width", "int", json!(node.width)),
+            ("executed", "boolean", json!(node.executed)),
+            ("actual_rows", "double", json!(node.actual_rows())),
+            ("actual_loops", "long", json!(node.loops)),
+            (
+                "actual_startup_time",
+                "double",
+                json!(node.startup_time.map(decimal)),
+            ),
+            (
+                "actual_total_time",
+                "double",
+                json!(node.total_time.map(decimal)),
+            ),
+            ("exclusive_time", "double", json!(node.time.map(decimal))),
+            (
+                "exclusive_time_accuracy",
+                "string",
+                json!(node.time.map(|_| node.accuracy.name())),
+            ),
+            (
+                "time_percent",
+                "double",
+                json!(node.time.map(|time| decimal(self.time_percent(time)))),
+            ),
+            ("workers", "int", json!(node.workers.len())),
+        ]
+    }
+
+    /// Per worker time and rows table, with the imbalanced values
+    /// highlighted.
+    fn participants_table(node: &Node) -> String {
+        use std::fmt::Write;
+
+        let participants = node.participants();
+        let mean_time =
+            participants.iter().map(|x| f64::from(x.1)).sum::<f64>() / participants.len() as f64;
+        let mean_rows = participants.iter().map(|x| x.2).sum::<f64>() / participants.len() as f64;
+        let bgcolor = |value: f64, mean: f64| {
+            if (value - mean).abs() > IMBALANCE * mean {
+                r##" bgcolor="#fddb61""##
+            } else {
+                ""
+            }
+        };
+
+        let mut table =
+            r#"<tr><td colspan="2"><table border="0" cellborder="1" cellspacing="0">"#.to_string();
+        table.push_str("<tr><td></td><td>Time</td><td>Rows</td></tr>");
+        for (name, time, rows) in &participants {
+            write!(
+                table,
+                r#"<tr><td align="left">{name}</td><td align="right"{}>{time:.2} ms</td><td align="right"{}>{rows}</td></tr>"#,
+                bgcolor(f64::from(*time), mean_time),
+                bgcolor(*rows, mean_rows),
+            )
+            .ok();
+        }
+        table.push_str("</table></td></tr>");
+
+        table
+    }
+
+    /// Solid version of the cost gradient used by the DOT output.
+    fn fill_color(&self, node: &Node) -> Option<String> {
+        let percent = self.cost_percent(node);
+
+        if percent < 0.1 {
+            None
+        } else {
+            Some(Self::color(percent))
+        }
+    }
+
     fn duration_color(percent: f32) -> &'static str {
         if percent > 90. {
             "#880000"
@@ -134,32 +1033,420 @@ impl Graph {
     }
 }
 
+/// How an exclusive time was obtained.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Accuracy {
+    #[default]
+    Exact,
+    /// Shared between several nodes, or subject to rounding errors
+    Estimated,
+    /// Negative because of the measurement overhead, replaced by 0
+    Clamped,
+}
+
+impl Accuracy {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Exact => "exact",
+            Self::Estimated => "estimated",
+            Self::Clamped => "clamped",
+        }
+    }
+}
+
+/// Prefix of the displayed time.
+impl std::fmt::Display for Accuracy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exact => Ok(()),
+            Self::Estimated => write!(f, "≈ "),
+            Self::Clamped => write!(f, "clamped "),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct Node {
     id: usize,
+    accuracy: Accuracy,
+    attributes: serde_json::Map<String, serde_json::Value>,
+    cache: Option<crate::Cache>,
     cost: f32,
+    details: Vec<String>,
     executed: bool,
+    /// Counterparts of the node in the sibling subtrees it stands for
+    group: Vec<Nd>,
+    /// Drawn by the first node of its group
+    hidden: bool,
+    inclusive_time: Option<f32>,
     info: String,
-    n_workers: usize,
+    loops: Option<usize>,
+    output: Vec<String>,
+    parallel_aware: bool,
+    parent_relationship: Option<String>,
+    /// Processes sharing the loops of the node: the parallel workers and the
+    /// leader
+    participants: usize,
     rows: u32,
+    startup_cost: f32,
+    startup_time: Option<f32>,
+    /// What a group of sibling subtrees stands for
+    summary: Option<String>,
     time: Option<f32>,
+    total_cost: f32,
+    total_time: Option<f32>,
     ty: String,
     subplan: Option<String>,
+    width: u32,
+    workers: Vec<crate::Worker>,
 }
 
 impl Node {
     fn from(id: usize, plan: &crate::Plan) -> Self {
         Self {
             id,
+            accuracy: Accuracy::Exact,
+            attributes: plan.attributes.clone(),
+            cache: match &plan.node {
+                crate::Node::Memoize { cache } => Some(cache.clone()),
+                _ => None,
+            },
             cost: Self::cost(plan),
+            details: Self::details(plan),
             executed: plan.actual_loops != Some(0),
+            group: Vec::new(),
+            hidden: false,
+            inclusive_time: None,
             info: Self::info(plan),
-            n_workers: plan.workers.len(),
+            loops: plan.actual_loops,
+            output: plan.output.clone(),
+            parallel_aware: plan.parallel_aware,
+            parent_relationship: plan.parent_relationship.clone(),
+            participants: 1,
             rows: plan.rows,
-            time: Self::time(plan),
+            startup_cost: plan.startup_cost,
+            startup_time: plan.actual_startup_time,
+            summary: None,
+            time: None,
+            total_cost: plan.total_cost,
+            total_time: plan.actual_total_time,
             ty: plan.node.to_string(),
             subplan: plan.subplan.clone(),
+            width: plan.width,
+            workers: plan.workers.clone(),
+        }
+    }
+
+    fn title(&self) -> String {
+        if self.info.is_empty() {
+            self.ty.clone()
+        } else {
+            format!("{} {}", self.ty, self.info)
+        }
+    }
+
+    fn attribute(&self, name: &str) -> Option<&serde_json::Value> {
+        self.attributes.get(name)
+    }
+
+    /// Is this node designated by its id or its relation name?
+    fn is(&self, name: &str) -> bool {
+        if let Ok(id) = name.parse::<usize>() {
+            return self.id == id;
+        }
+
+        self.relation().is_some_and(|relation| {
+            relation == name || relation.rsplit_once('.').is_some_and(|(_, x)| x == name)
+        })
+    }
+
+    /// Alias of the scanned table, without the suffix of the partitions.
+    fn table_alias(&self) -> Option<String> {
+        let alias = self.attribute("Alias")?.as_str()?;
+        let base = alias.trim_end_matches(|c: char| c.is_ascii_digit());
+
+        if base.len() < alias.len()
+            && let Some(base) = base.strip_suffix('_')
+        {
+            Some(base.to_string())
+        } else {
+            Some(alias.to_string())
+        }
+    }
+
+    fn relation(&self) -> Option<String> {
+        let name = self.attribute("Relation Name")?.as_str()?;
+
+        match self.attribute("Schema").and_then(|x| x.as_str()) {
+            Some(schema) => Some(format!("{schema}.{name}")),
+            None => Some(name.to_string()),
+        }
+    }
+
+    /// Time and rows of each process running a parallel node. The leader's
+    /// share is what remains from the totals, when it participates.
+    fn participants(&self) -> Vec<(String, f32, f64)> {
+        let mut participants = self
+            .workers
+            .iter()
+            .map(|worker| {
+                (
+                    format!("Worker {}", worker.number),
+                    worker.actual_total_time * worker.actual_loops as f32,
+                    (worker.actual_rows * worker.actual_loops) as f64,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let loops = self.loops.unwrap_or_default();
+        let worker_loops = self.workers.iter().map(|x| x.actual_loops).sum::<usize>();
+
+        if loops > worker_loops
+            && let (Some(time), Some(rows)) = (self.total_time, self.actual_rows())
+        {
+            let time = time * loops as f32 - participants.iter().map(|x| x.1).sum::<f32>();
+            let rows = rows * loops as f64 - participants.iter().map(|x| x.2).sum::<f64>();
+
+            participants.insert(0, ("Leader".to_string(), time.max(0.), rows.max(0.)));
+        }
+
+        participants
+    }
+
+    /// Does this node use the result of the subplan `name`, like
+    /// `InitPlan 1 (returns $0)` or `CTE name`?
+    fn references(&self, name: &str) -> bool {
+        if let Some(cte) = name.strip_prefix("CTE ") {
+            return self.ty == "CteScan"
+                && self.attribute("CTE Name").and_then(|x| x.as_str()) == Some(cte);
+        }
+
+        let (subplan, params) = match name.split_once(" (returns ") {
+            Some((subplan, params)) => (subplan, params.trim_end_matches(')')),
+            None => (name, ""),
+        };
+        let subplan = format!("({subplan})");
+
+        let uses = |text: &str| {
+            text.contains(&subplan)
+                || params.split(',').filter(|x| !x.is_empty()).any(|param| {
+                    text.match_indices(param).any(|(i, _)| {
+                        !text[i + param.len()..].starts_with(|c: char| c.is_ascii_digit())
+                    })
+                })
+        };
+
+        self.output.iter().any(|x| uses(x))
+            || self.attributes.values().any(|value| match value {
+                serde_json::Value::String(text) => uses(text),
+                serde_json::Value::Array(values) => {
+                    values.iter().filter_map(|x| x.as_str()).any(uses)
+                }
+                _ => false,
+            })
+    }
+
+    fn actual_rows(&self) -> Option<f64> {
+        self.attribute("Actual Rows")?.as_f64()
+    }
+
+    /// Rows read by a scan, including the ones removed by its filter.
+    fn scanned_rows(&self) -> f64 {
+        match self.actual_rows() {
+            Some(rows) => {
+                let removed = self
+                    .attribute("Rows Removed by Filter")
+                    .and_then(|x| x.as_f64())
+                    .unwrap_or_default();
+
+                (rows + removed) * self.loops.unwrap_or(1) as f64
+            }
+            None => f64::from(self.rows),
+        }
+    }
+
+    /// Ratio between the estimated and actual rows, always greater than 1.
+    fn misestimate(&self) -> Option<f64> {
+        if !self.executed {
+            return None;
+        }
+
+        let actual = self.actual_rows()?.max(1.);
+        let estimated = f64::from(self.rows).max(1.);
+
+        Some(actual.max(estimated) / actual.min(estimated))
+    }
+
+    fn spill(&self) -> Option<String> {
+        let number = |name| self.attribute(name).and_then(|x| x.as_u64());
+
+        if self.attribute("Sort Space Type").and_then(|x| x.as_str()) == Some("Disk") {
+            let method = self
+                .attribute("Sort Method")
+                .and_then(|x| x.as_str())
+                .unwrap_or("sort");
+
+            return Some(format!(
+                "{method} using {} kB on disk",
+                number("Sort Space Used").unwrap_or_default()
+            ));
+        }
+
+        if let Some(batches) = number("Hash Batches")
+            && batches > 1
+        {
+            return Some(format!("{batches} hash batches"));
+        }
+
+        if let Some(blocks) = number("Temp Written Blocks")
+            && blocks > 0
+        {
+            return Some(format!("{blocks} temporary blocks written"));
         }
+
+        None
+    }
+
+    /// Processes running the children of a Gather: the launched workers and
+    /// the leader, unless it only waits for them.
+    fn launched_participants(&self) -> usize {
+        let launched = self
+            .attribute("Workers Launched")
+            .and_then(|x| x.as_u64())
+            .unwrap_or_default() as usize;
+        let single_copy = self
+            .attribute("Single Copy")
+            .and_then(|x| x.as_bool())
+            .unwrap_or_default();
+
+        if single_copy { 1 } else { launched + 1 }
+    }
+
+    /// Wall-clock time of the node with its children, all loops included.
+    /// Times of parallel nodes are averages between the participants running
+    /// concurrently.
+    fn wall_time(&self) -> Option<f32> {
+        let time = self.total_time? * self.loops.unwrap_or(1) as f32;
+        let worker_loops = self.workers.iter().map(|x| x.actual_loops).sum::<usize>();
+
+        let participants = if !self.workers.is_empty() && self.loops == Some(worker_loops) {
+            self.workers.len()
+        } else {
+            self.participants
+        };
+
+        Some(time / participants.max(1) as f32)
+    }
+
+    /// Lines of details specific to the node type.
+    fn details(plan: &crate::Plan) -> Vec<String> {
+        let mut details = Vec::new();
+
+        match &plan.node {
+            crate::Node::Aggregate {
+                partial_mode,
+                strategy,
+                ..
+            } => {
+                if *strategy != crate::Strategy::Plain {
+                    details.push(format!("Strategy: {strategy}"));
+                }
+                if let Some(mode) = partial_mode
+                    && *mode != crate::PartialMode::Simple
+                {
+                    details.push(format!("Mode: {mode}"));
+                }
+            }
+            crate::Node::SetOp { strategy, .. } => {
+                details.push(format!("Strategy: {strategy}"));
+            }
+            crate::Node::WindowAgg {
+                run_condition: Some(condition),
+                ..
+            } => {
+                details.extend(wrap(
+                    &format!("Run condition: {condition}"),
+                    60,
+                    FILTER_LINES,
+                ));
+            }
+            crate::Node::Append {
+                subplans_removed, ..
+            }
+            | crate::Node::MergeAppend {
+                subplans_removed, ..
+            } if *subplans_removed > 0 => {
+                details.push(format!("Subplans removed: {subplans_removed}"));
+            }
+            crate::Node::ForeignScan {
+                operation,
+                relations,
+                remote_sql,
+                ..
+            } => {
+                if let Some(operation) = operation
+                    && *operation != crate::Operation::Select
+                {
+                    details.push(format!("Remote {operation}"));
+                }
+
+                let relations = relations.as_deref().unwrap_or_default();
+                let sql = remote_sql.as_deref().unwrap_or_default();
+                let pushed = [
+                    ("join", relations.contains(" JOIN ")),
+                    ("aggregate", relations.starts_with("Aggregate on")),
+                    ("sort", sql.contains(" ORDER BY ")),
+                    ("limit", sql.contains(" LIMIT ")),
+                ]
+                .iter()
+                .filter(|(_, pushed)| *pushed)
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>();
+                if !pushed.is_empty() {
+                    details.push(format!("Pushed down: {}", pushed.join(", ")));
+                }
+
+                if remote_sql.is_some() {
+                    details.push("Remote SQL:".to_string());
+                    details.extend(wrap(sql, 60, REMOTE_SQL_LINES));
+                }
+            }
+            crate::Node::Memoize { cache } => {
+                if let Some(mode) = &cache.mode {
+                    details.push(format!("Cache mode: {mode}"));
+                }
+                if let (Some(ratio), Some(hits), Some(misses)) =
+                    (cache.hit_ratio(), cache.hits, cache.misses)
+                {
+                    details.push(format!(
+                        "Hit ratio: {:.0} % ({hits} hits, {misses} misses)",
+                        ratio * 100.
+                    ));
+                }
+                if let (Some(evictions), Some(overflows)) = (cache.evictions, cache.overflows) {
+                    details.push(format!("Evictions: {evictions} | Overflows: {overflows}"));
+                }
+                if let Some(memory) = cache.peak_memory {
+                    details.push(format!("Peak memory: {memory} kB"));
+                }
+            }
+            _ => (),
+        }
+
+        if matches!(
+            plan.node,
+            crate::Node::CteScan { .. }
+                | crate::Node::FunctionScan { .. }
+                | crate::Node::SubqueryScan { .. }
+                | crate::Node::TableFunctionScan { .. }
+                | crate::Node::ValueScan { .. }
+                | crate::Node::WorkTableScan { .. }
+        ) && let Some(filter) = plan.attributes.get("Filter").and_then(|x| x.as_str())
+        {
+            details.extend(wrap(&format!("Filter: {filter}"), 60, FILTER_LINES));
+        }
+
+        details
     }
 
     fn info(plan: &crate::Plan) -> String {
@@ -171,43 +1458,85 @@ impl Node {
                     format!("by {}", keys.join(", "))
                 }
             }
+            crate::Node::Group { keys } if !keys.is_empty() => format!("by {}", keys.join(", ")),
             crate::Node::HashJoin {
                 join_type,
                 hash_cond,
                 ..
             } => format!("{join_type} join on {hash_cond}"),
+            crate::Node::ForeignScan {
+                relation: Some(relation),
+                ..
+            } => format!("on {relation}"),
+            crate::Node::ForeignScan {
+                relations: Some(relations),
+                ..
+            } => relations.clone(),
+            crate::Node::CteScan { name } => format!("on {name}"),
+            crate::Node::FunctionScan { alias, call, name }
+            | crate::Node::TableFunctionScan { alias, call, name } => {
+                let call = call.as_ref().unwrap_or(name);
+
+                if alias.is_empty() || call == alias {
+                    call.clone()
+                } else {
+                    format!("{call} as {alias}")
+                }
+            }
+            crate::Node::Memoize { cache } => format!("on {}", cache.key),
             crate::Node::Sort { keys, .. } => format!("by {}", keys.join(", ")),
             crate::Node::SeqScan { relation, .. } => format!("on {relation}"),
+            crate::Node::SetOp { command, .. } => command.to_lowercase(),
+            crate::Node::SubqueryScan { alias } | crate::Node::ValueScan { alias } => {
+                format!("on {alias}")
+            }
+            // PostgreSQL doesn't report the keys of a Unique, it uses the
+            // ones its input is sorted by.
+            crate::Node::Unique {} => plan
+                .plans
+                .first()
+                .and_then(|child| child.attributes.get("Sort Key")?.as_array())
+                .map(|keys| {
+                    let keys = keys.iter().filter_map(|x| x.as_str()).collect::<Vec<_>>();
+
+                    format!("by {}", keys.join(", "))
+                })
+                .unwrap_or_default(),
+            crate::Node::WindowAgg {
+                window: Some(window),
+                ..
+            } => Self::window(window),
+            crate::Node::WorkTableScan { name } => format!("on worktable of {name}"),
             _ => String::new(),
         };
 
         if info.len() > 80 {
-            format!("{}…", info.split_at(80).0)
+            format!("{}…", info.split_at(info.floor_char_boundary(80)).0)
         } else {
             info
         }
     }
 
-    fn time(plan: &crate::Plan) -> Option<f32> {
-        if let Some(mut time) = plan.actual_total_time {
-            if plan.workers.is_empty() {
-                time *= plan.actual_loops.unwrap_or(1) as f32;
-            }
-
-            for child in &plan.plans {
-                if child.parent_relationship != Some("InitPlan".to_string()) {
-                    time -= if child.workers.is_empty() {
-                        child.actual_total_time.unwrap_or_default()
-                            * child.actual_loops.unwrap_or(1) as f32
-                    } else {
-                        child.actual_total_time.unwrap_or_default()
-                    }
-                }
-            }
+    /// Partition and order keys of a window definition like
+    /// `w1 AS (PARTITION BY a ORDER BY b)`.
+    fn window(window: &str) -> String {
+        let definition = window
+            .split_once(" AS (")
+            .map_or(window, |(_, definition)| {
+                definition.strip_suffix(')').unwrap_or(definition)
+            });
+
+        let (partition, order) = match definition.split_once("ORDER BY ") {
+            Some((partition, order)) => (partition, order),
+            None => (definition, ""),
+        };
+        let partition = partition.trim().trim_start_matches("PARTITION BY ");
 
-            Some(time)
-        } else {
-            None
+        match (partition.is_empty(), order.is_empty()) {
+            (true, true) => "over all rows".to_string(),
+            (false, true) => format!("partition by {partition}"),
+            (true, false) => format!("order by {order}"),
+            (false, false) => format!("partition by {partition} order by {order}"),
         }
     }
 
@@ -258,8 +1587,32 @@ impl<'a> dot2::Labeller<'a> for Graph {
     fn node_label<'b>(&'b self, n: &Nd) -> dot2::Result<dot2::label::Text<'b>> {
         use std::fmt::Write;
 
-        let node = self.node(*n).unwrap();
-        let percent = node.cost / self.max_cost;
+        if self.is_stub(*n) {
+            let mut label = r#"<table border="0" cellborder="0" cellspacing="5">"#.to_string();
+            write!(
+                label,
+                r#"<tr><td align="left"><font color="gray">⋯ {} nodes</font></td></tr>"#,
+                self.descendants(*n) + 1
+            )
+            .ok();
+            if let Some(time) = self.nodes[*n].inclusive_time
+                && self.execution_time.is_some()
+            {
+                write!(
+                    label,
+                    r#"<tr><td align="left"><font color="gray">{time:.2} ms | {} %</font></td></tr>"#,
+                    self.time_percent(time)
+                )
+                .ok();
+            }
+            label.push_str("</table>");
+
+            return Ok(dot2::label::Text::HtmlStr(label.into()));
+        }
+
+        let merged = self.merged(*n);
+        let node = merged.as_ref();
+        let percent = self.cost_percent(node).min(1.);
         let color = Self::color(percent);
 
         let bgcolor = if percent < 0.1 {
@@ -271,16 +1624,18 @@ impl<'a> dot2::Labeller<'a> for Graph {
         };
 
         let time = if let Some(time) = node.time {
-            let time_percent = (time / self.execution_time.unwrap() * 100.).round().trunc();
+            let time_percent = self.time_percent(time);
+
+            let accuracy = node.accuracy;
 
             if !node.executed {
                 "<td><font color=\"gray\">Never executed</font></td>".to_string()
             } else if time < 1. {
-                format!("<td>&lt; 1 ms | {time_percent} %</td>")
+                format!("<td>{accuracy}&lt; 1 ms | {time_percent} %</td>")
             } else {
                 let color = Self::duration_color(time_percent);
 
-                format!("<td bgcolor=\"{color}\">{time:.2} ms | {time_percent} %</td>")
+                format!("<td bgcolor=\"{color}\">{accuracy}{time:.2} ms | {time_percent} %</td>")
             }
         } else {
             String::new()
@@ -296,16 +1651,64 @@ impl<'a> dot2::Labeller<'a> for Graph {
         write!(
             label,
             r#"<tr><td colspan="2" align="left">{}</td></tr>"#,
-            node.info
+            xml_escape(&node.info)
         )
         .ok();
-        if node.n_workers > 0 {
+        if let Some(summary) = &node.summary {
             write!(
                 label,
-                r#"<tr><td colspan="2" align="left">Workers: {}</td></tr>"#,
-                node.n_workers
+                r#"<tr><td colspan="2" align="left"><b>{summary}</b></td></tr>"#
             )
             .ok();
+            let relations = self.group_relations(*n);
+            if !relations.is_empty() {
+                write!(
+                    label,
+                    r#"<tr><td colspan="2" align="left">{}</td></tr>"#,
+                    xml_escape(&relations)
+                )
+                .ok();
+            }
+        }
+        for detail in &node.details {
+            write!(
+                label,
+                r#"<tr><td colspan="2" align="left">{}</td></tr>"#,
+                xml_escape(detail)
+            )
+            .ok();
+        }
+        if self.options.show_output && !node.output.is_empty() {
+            let output = format!("Output: {}", node.output.join(", "));
+
+            for line in wrap(&output, 60, OUTPUT_LINES) {
+                write!(
+                    label,
+                    r##"<tr><td colspan="2" align="left"><font color="#555555">{}</font></td></tr>"##,
+                    xml_escape(&line)
+                )
+                .ok();
+            }
+        }
+        if let Some(launched) = node.attribute("Workers Launched").and_then(|x| x.as_u64()) {
+            let planned = node
+                .attribute("Workers Planned")
+                .and_then(|x| x.as_u64())
+                .unwrap_or_default();
+            let color = if launched < planned {
+                "#cc0000"
+            } else {
+                "black"
+            };
+
+            write!(
+                label,
+                r#"<tr><td colspan="2" align="left"><font color="{color}">Workers: {launched} launched / {planned} planned</font></td></tr>"#
+            )
+            .ok();
+        }
+        if !node.workers.is_empty() {
+            label.push_str(&Self::participants_table(node));
         }
 
         write!(
@@ -320,6 +1723,37 @@ impl<'a> dot2::Labeller<'a> for Graph {
             node.rows
         )
         .ok();
+        if node.width >= WIDE_ROW {
+            write!(
+                label,
+                r##"<tr><td colspan="2" align="left" bgcolor="#fddb61">Width: {} bytes</td></tr>"##,
+                node.width
+            )
+            .ok();
+        }
+        if self.options.critical_path {
+            let hidden = self
+                .children(*n)
+                .filter(|child| !self.on_critical_path(*child))
+                .map(|child| self.descendants(child) + 1)
+                .sum::<usize>();
+
+            if hidden > 0 {
+                write!(
+                    label,
+                    r#"<tr><td colspan="2" align="left"><font color="gray">+{hidden} collapsed nodes</font></td></tr>"#
+                )
+                .ok();
+            }
+        }
+        for lint in lint::lints(self, *n) {
+            write!(
+                label,
+                r##"<tr><td colspan="2" align="left"><font color="#cc0000">⚠ {}</font></td></tr>"##,
+                xml_escape(&lint)
+            )
+            .ok();
+        }
         label.push_str("</table>");
 
         Ok(dot2::label::Text::HtmlStr(label.into()))
@@ -328,13 +1762,23 @@ impl<'a> dot2::Labeller<'a> for Graph {
     fn node_shape(&'a self, n: &Nd) -> Option<dot2::label::Text<'a>> {
         let node = self.node(*n)?;
 
-        let shape = if node.n_workers > 0 { "folder" } else { "box" };
+        let shape = if node.ty == "Foreign Scan" {
+            "box3d"
+        } else if !node.workers.is_empty() {
+            "folder"
+        } else {
+            "box"
+        };
 
         Some(dot2::label::Text::LabelStr(shape.into()))
     }
 
-    fn node_style(&'a self, _: &Nd) -> dot2::Style {
-        dot2::Style::Rounded
+    fn node_style(&'a self, n: &Nd) -> dot2::Style {
+        if self.is_stub(*n) {
+            dot2::Style::Dashed
+        } else {
+            dot2::Style::Rounded
+        }
     }
 
     fn node_color(&'a self, n: &Nd) -> Option<dot2::label::Text<'a>> {
@@ -347,6 +1791,60 @@ impl<'a> dot2::Labeller<'a> for Graph {
         }
     }
 
+    fn edge_label(&'a self, e: &Ed<'a>) -> dot2::label::Text<'a> {
+        let node = &self.nodes[e.1];
+
+        let label = if self.is_dependency(e) {
+            node.subplan.clone().unwrap_or_default()
+        } else if self.is_recursion(e) {
+            "recursion".to_string()
+        } else if self.shortcuts.contains(e) {
+            self.flow_label(e.1)
+        } else {
+            match node.parent_relationship.as_deref() {
+                Some(relationship @ ("Outer" | "Inner")) => {
+                    format!("{relationship}\n{}", self.flow_label(e.1))
+                }
+                _ => self.flow_label(e.1),
+            }
+        };
+
+        dot2::label::Text::EscStr(label.into())
+    }
+
+    fn edge_style(&'a self, e: &Ed<'a>) -> dot2::Style {
+        let relationship = self.nodes[e.1].parent_relationship.as_deref();
+
+        if self.is_dependency(e) {
+            dot2::Style::Dotted
+        } else if self.is_recursion(e) || matches!(relationship, Some("InitPlan" | "SubPlan")) {
+            dot2::Style::Dashed
+        } else if self.on_critical_path(e.1) {
+            dot2::Style::Bold
+        } else {
+            dot2::Style::None
+        }
+    }
+
+    fn edge_color(&'a self, e: &Ed<'a>) -> Option<dot2::label::Text<'a>> {
+        if self.is_dependency(e) {
+            return Some(dot2::label::Text::LabelStr("#808080".into()));
+        }
+        if self.is_recursion(e) {
+            return Some(dot2::label::Text::LabelStr("#9933cc".into()));
+        }
+
+        let color = if self.on_critical_path(e.1) {
+            "#0066cc"
+        } else {
+            "#bbbbbb"
+        };
+        // Graphviz draws a parallel line for each color of the list.
+        let colors = vec![color; self.flow_width(e.1)].join(":");
+
+        Some(dot2::label::Text::LabelStr(colors.into()))
+    }
+
     fn kind(&self) -> dot2::Kind {
         dot2::Kind::Graph
     }
@@ -361,7 +1859,9 @@ impl<'a> dot2::GraphWalk<'a> for Graph {
         let mut s: Vec<String> = self
             .nodes
             .iter()
-            .filter_map(|n| n.subplan.clone())
+            .enumerate()
+            .filter(|(k, _)| self.is_visible(*k))
+            .filter_map(|(_, n)| n.subplan.clone())
             .collect();
 
         s.dedup();
@@ -373,17 +1873,31 @@ impl<'a> dot2::GraphWalk<'a> for Graph {
         self.nodes
             .iter()
             .enumerate()
-            .filter(|(_, n)| n.subplan == Some(s.to_string()))
+            .filter(|(k, n)| self.is_visible(*k) && n.subplan == Some(s.to_string()))
             .map(|(k, _)| k)
             .collect()
     }
 
     fn nodes(&self) -> dot2::Nodes<'a, Nd> {
-        (0..self.nodes.len()).collect()
+        (0..self.nodes.len())
+            .filter(|n| self.is_visible(*n))
+            .collect()
     }
 
     fn edges(&'a self) -> dot2::Edges<'a, Ed<'a>> {
-        self.edges.iter().collect()
+        self.edges
+            .iter()
+            .chain(&self.shortcuts)
+            .filter(|(source, target)| self.is_visible(*source) && self.is_visible(*target))
+            .chain(
+                self.dependencies
+                    .iter()
+                    .chain(&self.recursions)
+                    .filter(|(source, target)| {
+                        self.is_visible(*source) && self.is_visible(*target)
+                    }),
+            )
+            .collect()
     }
 
     fn source(&self, e: &Ed<'_>) -> Nd {
@@ -0,0 +1,77 @@
+pub(super) fn violations(
+    baseline: &super::Graph,
+    plan: &super::Graph,
+    rules: &crate::check::Rules,
+) -> Vec<String> {
+    let mut violations = Vec::new();
+
+    let (Some(before), Some(after)) = (baseline.nodes.first(), plan.nodes.first()) else {
+        return violations;
+    };
+
+    if let Some(max) = rules.max_cost_increase
+        && before.total_cost > 0.
+    {
+        let increase = (after.total_cost - before.total_cost) / before.total_cost * 100.;
+
+        if increase > max {
+            violations.push(format!(
+                "total cost {:.2} is {increase:.0} % over the baseline {:.2} (max {max} %)",
+                after.total_cost, before.total_cost
+            ));
+        }
+    }
+
+    if let Some(max) = rules.max_rows_factor {
+        let (estimated, expected) = (after.rows.max(1) as f32, before.rows.max(1) as f32);
+        let factor = estimated.max(expected) / estimated.min(expected);
+
+        if factor > max {
+            violations.push(format!(
+                "estimated rows {} differ from the baseline {} by ×{factor:.1} (max ×{max})",
+                after.rows, before.rows
+            ));
+        }
+    }
+
+    for pattern in &rules.forbidden_nodes {
+        for node in plan.nodes.iter().filter(|node| matches(node, pattern)) {
+            violations.push(format!(
+                "forbidden {pattern}: node #{} {}",
+                node.id,
+                node.title()
+            ));
+        }
+    }
+
+    if rules.new_node_types {
+        let mut types = plan
+            .nodes
+            .iter()
+            .map(|node| &node.ty)
+            .filter(|ty| !baseline.nodes.iter().any(|node| &node.ty == *ty))
+            .collect::<Vec<_>>();
+        types.sort();
+        types.dedup();
+
+        for ty in types {
+            violations.push(format!("new node type {ty}, absent from the baseline"));
+        }
+    }
+
+    violations
+}
+
+/// Matches `<node type>` or `<node type> on <relation>`, the relation being
+/// optionally schema qualified.
+fn matches(node: &super::Node, pattern: &str) -> bool {
+    match pattern.split_once(" on ") {
+        Some((ty, relation)) => {
+            node.ty == ty
+                && node
+                    .relation()
+                    .is_some_and(|x| x == relation || x.rsplit('.').next() == Some(relation))
+        }
+        None => node.ty == pattern,
+    }
+}
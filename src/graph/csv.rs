@@ -0,0 +1,78 @@
+const HEADERS: [&str; 23] = [
+    "id",
+    "parent_id",
+    "depth",
+    "node_type",
+    "relation",
+    "index",
+    "total_cost",
+    "exclusive_cost",
+    "total_time",
+    "exclusive_time",
+    "exclusive_time_accuracy",
+    "loops",
+    "plan_rows",
+    "actual_rows",
+    "shared_hit_blocks",
+    "shared_read_blocks",
+    "shared_dirtied_blocks",
+    "shared_written_blocks",
+    "local_hit_blocks",
+    "local_read_blocks",
+    "temp_read_blocks",
+    "temp_written_blocks",
+    "subplan",
+];
+
+pub(super) fn render(graph: &super::Graph, delimiter: u8) -> String {
+    let mut writer = ::csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(Vec::new());
+
+    writer.write_record(HEADERS).unwrap();
+
+    for (n, node) in graph.nodes.iter().enumerate() {
+        let attribute = |name| {
+            node.attribute(name)
+                .map(ToString::to_string)
+                .unwrap_or_default()
+        };
+
+        writer
+            .write_record([
+                node.id.to_string(),
+                optional(graph.parent(n)),
+                graph.depth(n).to_string(),
+                node.ty.clone(),
+                node.relation().unwrap_or_default(),
+                node.attribute("Index Name")
+                    .and_then(|x| x.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                format!("{:.2}", node.total_cost),
+                format!("{:.2}", node.cost),
+                optional(node.inclusive_time.map(|x| format!("{x:.2}"))),
+                optional(node.time.map(|x| format!("{x:.2}"))),
+                optional(node.time.map(|_| node.accuracy.name())),
+                optional(node.loops),
+                node.rows.to_string(),
+                attribute("Actual Rows"),
+                attribute("Shared Hit Blocks"),
+                attribute("Shared Read Blocks"),
+                attribute("Shared Dirtied Blocks"),
+                attribute("Shared Written Blocks"),
+                attribute("Local Hit Blocks"),
+                attribute("Local Read Blocks"),
+                attribute("Temp Read Blocks"),
+                attribute("Temp Written Blocks"),
+                node.subplan.clone().unwrap_or_default(),
+            ])
+            .unwrap();
+    }
+
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|x| x.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,41 @@
+use serde_json::json;
+
+pub(super) fn render(graph: &super::Graph) -> String {
+    let nodes = (0..graph.nodes.len())
+        .map(|n| {
+            let mut data = graph
+                .metrics(n)
+                .into_iter()
+                .map(|(name, _, value)| (name.to_string(), value))
+                .collect::<serde_json::Map<_, _>>();
+            data.insert("id".to_string(), json!(format!("node{n}")));
+
+            json!({ "data": data })
+        })
+        .collect::<Vec<_>>();
+
+    let edges = graph
+        .edges
+        .iter()
+        .enumerate()
+        .map(|(k, (source, target))| {
+            json!({
+                "data": {
+                    "id": format!("edge{k}"),
+                    "source": format!("node{source}"),
+                    "target": format!("node{target}"),
+                    "relationship": graph.nodes[*target].parent_relationship,
+                },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let json = json!({
+        "elements": {
+            "nodes": nodes,
+            "edges": edges,
+        },
+    });
+
+    serde_json::to_string_pretty(&json).unwrap()
+}
@@ -0,0 +1,422 @@
+use super::{Graph, Nd, Node};
+use std::fmt::Write as _;
+
+#[cfg(test)]
+mod tests;
+
+/// Relative change above which a matched node is considered changed.
+const THRESHOLD: f32 = 0.1;
+
+type Ed<'a> = &'a (usize, usize);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Status {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+#[derive(Debug)]
+struct Entry {
+    before: Option<Nd>,
+    after: Option<Nd>,
+    status: Status,
+}
+
+#[derive(Debug)]
+pub(super) struct Diff {
+    before: Graph,
+    after: Graph,
+    entries: Vec<Entry>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl Diff {
+    pub(super) fn from(before: Graph, after: Graph) -> Self {
+        let mut diff = Self {
+            before,
+            after,
+            entries: Vec::new(),
+            edges: Vec::new(),
+        };
+
+        match (diff.before.nodes.is_empty(), diff.after.nodes.is_empty()) {
+            (false, false) if key(&diff.before, 0) == key(&diff.after, 0) => {
+                diff.pair(None, 0, 0);
+            }
+            (before_empty, after_empty) => {
+                if !before_empty {
+                    diff.subtree(None, 0, Status::Removed);
+                }
+                if !after_empty {
+                    diff.subtree(None, 0, Status::Added);
+                }
+            }
+        }
+
+        diff
+    }
+
+    pub(super) fn render(&self) -> String {
+        let mut output = Vec::new();
+
+        dot2::render(self, &mut output).unwrap();
+
+        std::str::from_utf8(&output).unwrap().to_string()
+    }
+
+    pub(super) fn markdown(&self) -> String {
+        let mut output = "## Explain diff\n\n".to_string();
+
+        output.push_str("| | Before | After | Δ |\n");
+        output.push_str("|---|---|---|---|\n");
+        writeln!(
+            output,
+            "| Total cost | {} |",
+            change(
+                self.before.nodes.first().map(|x| x.total_cost),
+                self.after.nodes.first().map(|x| x.total_cost),
+            )
+            .join(" | ")
+        )
+        .ok();
+        writeln!(
+            output,
+            "| Execution time | {} |",
+            change(self.before.execution_time, self.after.execution_time).join(" | ")
+        )
+        .ok();
+        writeln!(
+            output,
+            "| Nodes | {} | {} | {:+} |",
+            self.before.nodes.len(),
+            self.after.nodes.len(),
+            self.after.nodes.len() as isize - self.before.nodes.len() as isize,
+        )
+        .ok();
+
+        for (title, status) in [
+            ("Added nodes", Status::Added),
+            ("Removed nodes", Status::Removed),
+        ] {
+            let entries = self.entries(status);
+
+            if entries.is_empty() {
+                continue;
+            }
+
+            writeln!(output, "\n### {title}\n").ok();
+            for entry in entries {
+                writeln!(output, "- {}", self.name(entry)).ok();
+            }
+        }
+
+        let entries = self.entries(Status::Changed);
+        if !entries.is_empty() {
+            output.push_str("\n### Changed nodes\n\n");
+            output.push_str("| Node | Cost | Rows | Time |\n");
+            output.push_str("|---|---|---|---|\n");
+
+            for entry in entries {
+                let (before, after) = self.nodes(entry);
+                let (before, after) = (before.unwrap(), after.unwrap());
+
+                writeln!(
+                    output,
+                    "| {} | {} | {} | {} |",
+                    self.name(entry),
+                    change_label(Some(before.cost), Some(after.cost), 2),
+                    change_label(Some(before.rows as f32), Some(after.rows as f32), 0),
+                    change_label(before.time, after.time, 2),
+                )
+                .ok();
+            }
+        }
+
+        output
+    }
+
+    fn pair(&mut self, parent: Option<usize>, before: Nd, after: Nd) {
+        let status = if changed(&self.before.nodes[before], &self.after.nodes[after]) {
+            Status::Changed
+        } else {
+            Status::Unchanged
+        };
+        let id = self.push(parent, Some(before), Some(after), status);
+
+        let before_children = self.before.children(before).collect::<Vec<_>>();
+        let after_children = self.after.children(after).collect::<Vec<_>>();
+
+        for (before, after) in self.align(&before_children, &after_children) {
+            match (before, after) {
+                (Some(before), Some(after)) => self.pair(Some(id), before, after),
+                (Some(before), None) => self.subtree(Some(id), before, Status::Removed),
+                (None, Some(after)) => self.subtree(Some(id), after, Status::Added),
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    fn subtree(&mut self, parent: Option<usize>, n: Nd, status: Status) {
+        let graph = if status == Status::Removed {
+            &self.before
+        } else {
+            &self.after
+        };
+        let children = graph.children(n).collect::<Vec<_>>();
+
+        let id = if status == Status::Removed {
+            self.push(parent, Some(n), None, status)
+        } else {
+            self.push(parent, None, Some(n), status)
+        };
+
+        for child in children {
+            self.subtree(Some(id), child, status);
+        }
+    }
+
+    fn push(
+        &mut self,
+        parent: Option<usize>,
+        before: Option<Nd>,
+        after: Option<Nd>,
+        status: Status,
+    ) -> usize {
+        let id = self.entries.len();
+
+        self.entries.push(Entry {
+            before,
+            after,
+            status,
+        });
+
+        if let Some(parent) = parent {
+            self.edges.push((parent, id));
+        }
+
+        id
+    }
+
+    /// Aligns two children lists on their longest common subsequence of keys.
+    fn align(&self, before: &[Nd], after: &[Nd]) -> Vec<(Option<Nd>, Option<Nd>)> {
+        let (n, m) = (before.len(), after.len());
+        let mut lcs = vec![vec![0; m + 1]; n + 1];
+
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if key(&self.before, before[i]) == key(&self.after, after[j]) {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut alignment = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < n || j < m {
+            if i < n && j < m && key(&self.before, before[i]) == key(&self.after, after[j]) {
+                alignment.push((Some(before[i]), Some(after[j])));
+                i += 1;
+                j += 1;
+            } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                alignment.push((Some(before[i]), None));
+                i += 1;
+            } else {
+                alignment.push((None, Some(after[j])));
+                j += 1;
+            }
+        }
+
+        alignment
+    }
+
+    fn entries(&self, status: Status) -> Vec<&Entry> {
+        self.entries.iter().filter(|x| x.status == status).collect()
+    }
+
+    fn nodes(&self, entry: &Entry) -> (Option<&Node>, Option<&Node>) {
+        (
+            entry.before.map(|n| &self.before.nodes[n]),
+            entry.after.map(|n| &self.after.nodes[n]),
+        )
+    }
+
+    fn node(&self, entry: &Entry) -> &Node {
+        let (before, after) = self.nodes(entry);
+
+        after.or(before).unwrap()
+    }
+
+    fn name(&self, entry: &Entry) -> String {
+        let node = self.node(entry);
+
+        format!("#{} {}", node.id, node.title())
+            .replace('|', "\\|")
+            .replace('`', "'")
+    }
+}
+
+fn key(graph: &Graph, n: Nd) -> (&str, Option<String>) {
+    let node = &graph.nodes[n];
+
+    (&node.ty, node.relation())
+}
+
+fn changed(before: &Node, after: &Node) -> bool {
+    let differ = |before: f32, after: f32| {
+        let delta = (after - before).abs();
+
+        delta >= 1. && delta > THRESHOLD * before.abs().max(after.abs())
+    };
+
+    before.info != after.info
+        || differ(before.rows as f32, after.rows as f32)
+        || differ(before.cost, after.cost)
+        || match (before.time, after.time) {
+            (Some(before), Some(after)) => differ(before, after),
+            _ => false,
+        }
+}
+
+/// Before, after and delta columns.
+fn change(before: Option<f32>, after: Option<f32>) -> [String; 3] {
+    let value = |x: Option<f32>| x.map_or_else(|| "-".to_string(), |x| format!("{x:.2}"));
+    let delta = match (before, after) {
+        (Some(before), Some(after)) => format!("{:+.2}", after - before),
+        _ => "-".to_string(),
+    };
+
+    [value(before), value(after), delta]
+}
+
+fn change_label(before: Option<f32>, after: Option<f32>, precision: usize) -> String {
+    match (before, after) {
+        (Some(before), Some(after)) => {
+            format!(
+                "{before:.precision$} → {after:.precision$} ({:+.precision$})",
+                after - before
+            )
+        }
+        (Some(value), None) | (None, Some(value)) => format!("{value:.precision$}"),
+        (None, None) => String::new(),
+    }
+}
+
+impl<'a> dot2::Labeller<'a> for Diff {
+    type Node = Nd;
+    type Edge = Ed<'a>;
+    type Subgraph = ();
+
+    fn graph_id(&'a self) -> dot2::Result<dot2::Id<'a>> {
+        dot2::Id::new("diff")
+    }
+
+    fn node_id(&'a self, n: &Nd) -> dot2::Result<dot2::Id<'a>> {
+        dot2::Id::new(format!("node{n}"))
+    }
+
+    fn node_label<'b>(&'b self, n: &Nd) -> dot2::Result<dot2::label::Text<'b>> {
+        let entry = &self.entries[*n];
+        let (before, after) = self.nodes(entry);
+        let node = self.node(entry);
+
+        let status = match entry.status {
+            Status::Added => " (added)",
+            Status::Removed => " (removed)",
+            Status::Changed => " (changed)",
+            Status::Unchanged => "",
+        };
+
+        let mut label = r#"<table border="0" cellborder="0" cellspacing="5">"#.to_string();
+        write!(
+            label,
+            r#"<tr><td align="left"><b>{}</b>{status}</td></tr>"#,
+            node.ty
+        )
+        .ok();
+        write!(
+            label,
+            r#"<tr><td align="left">{}</td></tr>"#,
+            super::xml_escape(&node.info)
+        )
+        .ok();
+        for (name, before, after, precision) in [
+            ("Cost", before.map(|x| x.cost), after.map(|x| x.cost), 2),
+            (
+                "Rows",
+                before.map(|x| x.rows as f32),
+                after.map(|x| x.rows as f32),
+                0,
+            ),
+            (
+                "Time",
+                before.and_then(|x| x.time),
+                after.and_then(|x| x.time),
+                2,
+            ),
+        ] {
+            let change = change_label(before, after, precision);
+
+            if !change.is_empty() {
+                write!(
+                    label,
+                    r#"<tr><td align="left">{name}: {}</td></tr>"#,
+                    super::xml_escape(&change)
+                )
+                .ok();
+            }
+        }
+        label.push_str("</table>");
+
+        Ok(dot2::label::Text::HtmlStr(label.into()))
+    }
+
+    fn node_shape(&'a self, _: &Nd) -> Option<dot2::label::Text<'a>> {
+        Some(dot2::label::Text::LabelStr("box".into()))
+    }
+
+    fn node_style(&'a self, _: &Nd) -> dot2::Style {
+        dot2::Style::Rounded
+    }
+
+    fn node_color(&'a self, n: &Nd) -> Option<dot2::label::Text<'a>> {
+        let color = match self.entries[*n].status {
+            Status::Added => "#00aa00",
+            Status::Removed => "#cc0000",
+            Status::Changed => "#ee8800",
+            Status::Unchanged => return None,
+        };
+
+        Some(dot2::label::Text::LabelStr(color.into()))
+    }
+
+    fn kind(&self) -> dot2::Kind {
+        dot2::Kind::Graph
+    }
+}
+
+impl<'a> dot2::GraphWalk<'a> for Diff {
+    type Node = Nd;
+    type Edge = Ed<'a>;
+    type Subgraph = ();
+
+    fn nodes(&self) -> dot2::Nodes<'a, Nd> {
+        (0..self.entries.len()).collect()
+    }
+
+    fn edges(&'a self) -> dot2::Edges<'a, Ed<'a>> {
+        self.edges.iter().collect()
+    }
+
+    fn source(&self, e: &Ed<'_>) -> Nd {
+        e.0
+    }
+
+    fn target(&self, e: &Ed<'_>) -> Nd {
+        e.1
+    }
+}
@@ -0,0 +1,82 @@
+use super::{Diff, Status};
+use crate::graph::Graph;
+
+fn plan() -> serde_json::Value {
+    serde_json::from_str(include_str!("../../../examples/plan_2.json")).unwrap()
+}
+
+fn graph(plan: serde_json::Value) -> Graph {
+    let explains: Vec<crate::Explain> = serde_json::from_value(plan).unwrap();
+
+    Graph::from(&explains[0])
+}
+
+fn statuses(diff: &Diff) -> Vec<Status> {
+    diff.entries.iter().map(|x| x.status).collect()
+}
+
+#[test]
+fn identical() {
+    let diff = Diff::from(graph(plan()), graph(plan()));
+
+    assert_eq!(diff.entries.len(), diff.before.nodes.len());
+    assert!(statuses(&diff).iter().all(|x| *x == Status::Unchanged));
+}
+
+#[test]
+fn inserted_child() {
+    let mut after = plan();
+    after[0]["Plan"]["Plans"].as_array_mut().unwrap().insert(
+        0,
+        serde_json::json!({
+            "Node Type": "Result",
+            "Parent Relationship": "Outer",
+            "Startup Cost": 0.0,
+            "Total Cost": 0.0,
+            "Plan Rows": 1,
+            "Plan Width": 4,
+        }),
+    );
+
+    let diff = Diff::from(graph(plan()), graph(after));
+
+    let added = diff.entries(Status::Added);
+    assert_eq!(added.len(), 1);
+    assert_eq!(diff.node(added[0]).ty, "Result");
+    assert!(diff.entries(Status::Removed).is_empty());
+    assert_eq!(
+        diff.entries(Status::Unchanged).len(),
+        diff.before.nodes.len()
+    );
+}
+
+#[test]
+fn changed_root_type() {
+    let mut after = plan();
+    after[0]["Plan"]["Node Type"] = "Materialize".into();
+
+    let diff = Diff::from(graph(plan()), graph(after));
+
+    assert_eq!(diff.entries(Status::Removed).len(), diff.before.nodes.len());
+    assert_eq!(diff.entries(Status::Added).len(), diff.after.nodes.len());
+    assert!(diff.entries(Status::Unchanged).is_empty());
+}
+
+#[test]
+fn changed_cost() {
+    let mut after = plan();
+    after[0]["Plan"]["Total Cost"] = 1_000.0.into();
+
+    let diff = Diff::from(graph(plan()), graph(after));
+
+    assert_eq!(diff.entries[0].status, Status::Changed);
+    assert!(statuses(&diff)[1..].iter().all(|x| *x == Status::Unchanged));
+
+    // Below the threshold, the change is noise.
+    let mut after = plan();
+    after[0]["Plan"]["Total Cost"] = 626.0.into();
+
+    let diff = Diff::from(graph(plan()), graph(after));
+
+    assert_eq!(diff.entries[0].status, Status::Unchanged);
+}
@@ -0,0 +1,128 @@
+use std::fmt::Write as _;
+
+const BOX_WIDTH: f32 = 240.;
+const LINE_HEIGHT: f32 = 18.;
+const H_GAP: f32 = 30.;
+const V_GAP: f32 = 50.;
+
+pub(super) fn render(graph: &super::Graph) -> String {
+    let positions = layout(graph);
+
+    let mut cells = String::new();
+
+    for (n, node) in graph.nodes.iter().enumerate() {
+        let lines = graph.label_lines(node);
+        let mut value = format!("<b>{}</b>", super::xml_escape(&lines[0]));
+        for line in &lines[1..] {
+            write!(value, "<br>{}", super::xml_escape(line)).ok();
+        }
+
+        let mut style =
+            "rounded=1;whiteSpace=wrap;html=1;align=left;verticalAlign=top;spacingLeft=5;"
+                .to_string();
+        if let Some(color) = graph.fill_color(node) {
+            write!(style, "fillColor={color};").ok();
+        }
+        if !node.executed {
+            style.push_str("strokeColor=#808080;fontColor=#808080;");
+        }
+
+        let (x, y) = positions[n];
+        writeln!(
+            cells,
+            r#"        <mxCell id="node{n}" value="{}" style="{style}" vertex="1" parent="1">"#,
+            super::xml_escape(&value),
+        )
+        .ok();
+        writeln!(
+            cells,
+            r#"          <mxGeometry x="{x}" y="{y}" width="{BOX_WIDTH}" height="{}" as="geometry"/>"#,
+            height(&lines),
+        )
+        .ok();
+        cells.push_str("        </mxCell>\n");
+    }
+
+    for (k, (source, target)) in graph.edges.iter().enumerate() {
+        writeln!(
+            cells,
+            r#"        <mxCell id="edge{k}" style="endArrow=none;" edge="1" source="node{source}" target="node{target}" parent="1">"#,
+        )
+        .ok();
+        cells.push_str(r#"          <mxGeometry relative="1" as="geometry"/>"#);
+        cells.push_str("\n        </mxCell>\n");
+    }
+
+    format!(
+        r#"<mxfile host="explain">
+  <diagram id="explain" name="explain">
+    <mxGraphModel>
+      <root>
+        <mxCell id="0"/>
+        <mxCell id="1" parent="0"/>
+{cells}      </root>
+    </mxGraphModel>
+  </diagram>
+</mxfile>
+"#
+    )
+}
+
+/// Top-down tree layout: leaves are laid out side by side and each parent is
+/// centered above its children.
+fn layout(graph: &super::Graph) -> Vec<(f32, f32)> {
+    let mut positions = vec![(0., 0.); graph.nodes.len()];
+
+    let mut levels = Vec::<f32>::new();
+    for (n, node) in graph.nodes.iter().enumerate() {
+        let depth = graph.depth(n);
+        let height = height(&graph.label_lines(node));
+
+        if levels.len() <= depth {
+            levels.resize(depth + 1, 0.);
+        }
+        levels[depth] = levels[depth].max(height);
+    }
+
+    let mut next_leaf = 0.;
+    if !graph.nodes.is_empty() {
+        place(graph, 0, 0., &levels, &mut next_leaf, &mut positions);
+    }
+
+    positions
+}
+
+fn place(
+    graph: &super::Graph,
+    n: usize,
+    y: f32,
+    levels: &[f32],
+    next_leaf: &mut f32,
+    positions: &mut [(f32, f32)],
+) {
+    let depth = graph.depth(n);
+    let children = graph.children(n).collect::<Vec<_>>();
+
+    let x = if children.is_empty() {
+        let x = *next_leaf;
+        *next_leaf += BOX_WIDTH + H_GAP;
+        x
+    } else {
+        let child_y = y + levels[depth] + V_GAP;
+
+        for child in &children {
+            place(graph, *child, child_y, levels, next_leaf, positions);
+        }
+
+        let first = positions[children[0]].0;
+        let last = positions[children[children.len() - 1]].0;
+
+        (first + last) / 2.
+    };
+
+    positions[n] = (x, y);
+}
+
+fn height(lines: &[String]) -> f32 {
+    lines.len() as f32 * LINE_HEIGHT + 10.
+}
@@ -0,0 +1,59 @@
+use std::fmt::Write as _;
+
+/// One line per node with its ancestors stack and its exclusive time in
+/// microseconds, or its exclusive cost in hundredths for plans without timing.
+pub(super) fn render(graph: &super::Graph) -> String {
+    let mut output = String::new();
+
+    for (n, node) in graph.nodes.iter().enumerate() {
+        let weight = weight(node);
+
+        if weight == 0 {
+            continue;
+        }
+
+        let mut stack = vec![frame(node)];
+        let mut parent = graph.parent(n);
+
+        while let Some(p) = parent {
+            stack.push(frame(&graph.nodes[p]));
+            parent = graph.parent(p);
+        }
+
+        stack.reverse();
+        writeln!(output, "{} {weight}", stack.join(";")).ok();
+    }
+
+    output
+}
+
+pub(super) fn flamegraph(graph: &super::Graph) -> crate::Result<String> {
+    let folded = render(graph);
+    let mut output = Vec::new();
+
+    let mut options = inferno::flamegraph::Options::default();
+    options.title = "Explain".to_string();
+    options.count_name = if graph.execution_time.is_some() {
+        "µs".to_string()
+    } else {
+        "cost × 0.01".to_string()
+    };
+
+    inferno::flamegraph::from_lines(&mut options, folded.lines(), &mut output)
+        .map_err(|err| crate::Error::Flamegraph(err.to_string()))?;
+
+    Ok(String::from_utf8(output).unwrap())
+}
+
+fn weight(node: &super::Node) -> u64 {
+    let weight = match node.time {
+        Some(time) => time * 1_000.,
+        None => node.cost * 100.,
+    };
+
+    weight.max(0.).round() as u64
+}
+
+fn frame(node: &super::Node) -> String {
+    node.title().replace(';', ",")
+}
@@ -0,0 +1,62 @@
+use std::fmt::Write as _;
+
+pub(super) fn render(graph: &super::Graph) -> String {
+    let mut output = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+"#
+    .to_string();
+
+    if !graph.nodes.is_empty() {
+        for (name, ty, _) in graph.metrics(0) {
+            writeln!(
+                output,
+                r#"  <key id="{name}" for="node" attr.name="{name}" attr.type="{ty}"/>"#
+            )
+            .ok();
+        }
+    }
+    output.push_str(
+        r#"  <key id="relationship" for="edge" attr.name="relationship" attr.type="string"/>"#,
+    );
+    output.push_str("\n  <graph id=\"explain\" edgedefault=\"directed\">\n");
+
+    for n in 0..graph.nodes.len() {
+        writeln!(output, r#"    <node id="node{n}">"#).ok();
+        for (name, _, value) in graph.metrics(n) {
+            let value = match value {
+                serde_json::Value::Null => continue,
+                serde_json::Value::String(s) => s,
+                value => value.to_string(),
+            };
+
+            writeln!(
+                output,
+                r#"      <data key="{name}">{}</data>"#,
+                super::xml_escape(&value)
+            )
+            .ok();
+        }
+        output.push_str("    </node>\n");
+    }
+
+    for (source, target) in &graph.edges {
+        writeln!(
+            output,
+            r#"    <edge source="node{source}" target="node{target}">"#
+        )
+        .ok();
+        if let Some(relationship) = &graph.nodes[*target].parent_relationship {
+            writeln!(
+                output,
+                r#"      <data key="relationship">{}</data>"#,
+                super::xml_escape(relationship)
+            )
+            .ok();
+        }
+        output.push_str("    </edge>\n");
+    }
+
+    output.push_str("  </graph>\n</graphml>\n");
+
+    output
+}
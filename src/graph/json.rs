@@ -0,0 +1,73 @@
+use super::decimal;
+use serde_json::json;
+
+/// Version of the `--format json` schema, described in `docs/JSON.md`.
+const VERSION: u32 = 1;
+
+pub(super) fn render(graph: &super::Graph) -> String {
+    let plan = if graph.nodes.is_empty() {
+        serde_json::Value::Null
+    } else {
+        node(graph, 0)
+    };
+
+    let json = json!({
+        "version": VERSION,
+        "planning_time": graph.planning_time.map(decimal),
+        "execution_time": graph.execution_time.map(decimal),
+        "max_cost": decimal(graph.max_cost),
+        "plan": plan,
+    });
+
+    serde_json::to_string_pretty(&json).unwrap()
+}
+
+fn node(graph: &super::Graph, n: usize) -> serde_json::Value {
+    let node = &graph.nodes[n];
+
+    let workers = node
+        .workers
+        .iter()
+        .map(|worker| {
+            json!({
+                "number": worker.number,
+                "actual_startup_time": decimal(worker.actual_startup_time),
+                "actual_total_time": decimal(worker.actual_total_time),
+                "actual_rows": worker.actual_rows,
+                "actual_loops": worker.actual_loops,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let plans = graph
+        .children(n)
+        .map(|child| self::node(graph, child))
+        .collect::<Vec<_>>();
+
+    json!({
+        "id": node.id,
+        "parent_id": graph.parent(n),
+        "depth": graph.depth(n),
+        "node_type": node.ty,
+        "info": node.info,
+        "subplan": node.subplan,
+        "parent_relationship": node.parent_relationship,
+        "startup_cost": decimal(node.startup_cost),
+        "total_cost": decimal(node.total_cost),
+        "exclusive_cost": decimal(node.cost),
+        "cost_percent": decimal(graph.cost_percent(node) * 100.),
+        "plan_rows": node.rows,
+        "plan_width": node.width,
+        "executed": node.executed,
+        "actual_startup_time": node.startup_time.map(decimal),
+        "actual_total_time": node.total_time.map(decimal),
+        "actual_loops": node.loops,
+        "exclusive_time": node.time.map(decimal),
+        "exclusive_time_accuracy": node.time.map(|_| node.accuracy.name()),
+        "time_percent": node.time.map(|time| decimal(graph.time_percent(time))),
+        "output": node.output,
+        "workers": workers,
+        "attributes": node.attributes,
+        "plans": plans,
+    })
+}
@@ -0,0 +1,150 @@
+use super::{BIG_RELATION, MISESTIMATE, WIDE_ROW};
+use std::fmt::Write as _;
+
+/// Share of the rows read kept by a filter below which it is highly
+/// selective.
+const SELECTIVITY: f64 = 0.01;
+/// Loops of a nested loop inner side considered as huge.
+const LOOPS: usize = 1_000;
+/// Memoize hit ratio below which the cache is ineffective.
+const HIT_RATIO: f64 = 0.5;
+
+pub(super) fn report(graph: &super::Graph) -> String {
+    let mut output = String::new();
+
+    for (n, node) in graph.nodes.iter().enumerate() {
+        let lints = lints(graph, n);
+
+        if lints.is_empty() {
+            continue;
+        }
+
+        writeln!(output, "#{} {}", node.id, node.title()).ok();
+        for lint in lints {
+            writeln!(output, "  - {lint}").ok();
+        }
+    }
+
+    if output.is_empty() {
+        output.push_str("No warnings\n");
+    }
+
+    output
+}
+
+pub(super) fn lints(graph: &super::Graph, n: usize) -> Vec<String> {
+    let node = &graph.nodes[n];
+    let number = |name| node.attribute(name).and_then(|x| x.as_f64());
+
+    let mut lints = Vec::new();
+
+    if !node.executed {
+        if graph
+            .parent(n)
+            .is_none_or(|parent| graph.nodes[parent].executed)
+        {
+            lints.push("never executed".to_string());
+        }
+
+        return lints;
+    }
+
+    if node.ty == "Seq Scan"
+        && let Some(rows) = node.actual_rows()
+        && number("Rows Removed by Filter").is_some()
+    {
+        let read = node.scanned_rows();
+        let kept = rows * node.loops.unwrap_or(1) as f64 / read;
+
+        if read >= BIG_RELATION && kept < SELECTIVITY {
+            lints.push(format!(
+                "the filter keeps {:.2} % of the {read:.0} rows read, an index may help",
+                kept * 100.
+            ));
+        }
+    }
+
+    if node.ty == "Nested Loop" {
+        let inner = graph
+            .children(n)
+            .map(|child| &graph.nodes[child])
+            .find(|child| child.parent_relationship.as_deref() == Some("Inner"));
+
+        if let Some(loops) = inner.and_then(|x| x.loops)
+            && loops >= LOOPS
+        {
+            lints.push(format!(
+                "inner side executed {loops} times, a hash or merge join may be cheaper"
+            ));
+        }
+    }
+
+    if let Some(cache) = &node.cache {
+        if let Some(ratio) = cache.hit_ratio()
+            && ratio < HIT_RATIO
+        {
+            lints.push(format!(
+                "cache hit ratio of {:.0} %, a hash join may be cheaper than the nested loop",
+                ratio * 100.
+            ));
+        }
+
+        let evictions = cache.evictions.unwrap_or_default() + cache.overflows.unwrap_or_default();
+        if evictions > 0 {
+            lints.push(format!(
+                "{evictions} cache evictions or overflows, the cache doesn't fit in work_mem and a hash join may be cheaper"
+            ));
+        }
+    }
+
+    if matches!(
+        node.ty.as_str(),
+        "Hash" | "Incremental Sort" | "Materialize" | "Sort"
+    ) && node.width >= WIDE_ROW
+    {
+        lints.push(format!(
+            "rows of {} bytes go through the {}, only select the needed columns",
+            node.width,
+            node.ty.to_lowercase()
+        ));
+    }
+
+    if let Some(factor) = node.misestimate()
+        && factor >= MISESTIMATE
+    {
+        lints.push(format!(
+            "rows misestimated ×{factor:.0}, run ANALYZE or raise the statistics target"
+        ));
+    }
+
+    if let Some(spill) = node.spill() {
+        lints.push(format!("{spill}, work_mem may be too low"));
+    }
+
+    if let (Some(planned), Some(launched)) = (number("Workers Planned"), number("Workers Launched"))
+        && launched < planned
+    {
+        lints.push(format!(
+            "{launched} workers launched out of {planned} planned, max_parallel_workers may be too low"
+        ));
+    }
+
+    if let Some(blocks) = number("Lossy Heap Blocks")
+        && blocks > 0.
+    {
+        lints.push(format!(
+            "{blocks} lossy heap blocks, work_mem is too low to keep the bitmap exact"
+        ));
+    }
+
+    if node.ty == "Index Only Scan"
+        && let Some(fetches) = number("Heap Fetches")
+        && fetches > 0.
+    {
+        lints.push(format!(
+            "{fetches} heap fetches, VACUUM the table to update its visibility map"
+        ));
+    }
+
+    lints
+}
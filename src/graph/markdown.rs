@@ -0,0 +1,167 @@
+use super::{BIG_RELATION, MISESTIMATE};
+use std::fmt::Write as _;
+
+const TOP: usize = 5;
+
+pub(super) fn render(graph: &super::Graph) -> String {
+    let mut output = "## Explain\n\n".to_string();
+
+    output.push_str("| Planning time | Execution time | Total cost |\n");
+    output.push_str("|---------------|----------------|------------|\n");
+    writeln!(
+        output,
+        "| {} | {} | {:.2} |",
+        duration(graph.planning_time),
+        duration(graph.execution_time),
+        graph.nodes.first().map_or(0., |x| x.total_cost),
+    )
+    .ok();
+
+    let mut nodes = graph.nodes.iter().collect::<Vec<_>>();
+
+    if graph.execution_time.is_some() {
+        nodes.sort_by(|a, b| {
+            b.time
+                .unwrap_or_default()
+                .total_cmp(&a.time.unwrap_or_default())
+        });
+
+        let rows = nodes
+            .iter()
+            .take(TOP)
+            .map(|node| {
+                let time = node.time.unwrap_or_default();
+
+                vec![
+                    name(node),
+                    format!("{time:.2} ms"),
+                    format!("{} %", graph.time_percent(time)),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        section(
+            &mut output,
+            &format!("Top {TOP} nodes by time"),
+            &["Node", "Time", "%"],
+            &rows,
+        );
+    }
+
+    nodes.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+    let rows = nodes
+        .iter()
+        .take(TOP)
+        .map(|node| {
+            vec![
+                name(node),
+                format!("{:.2}", node.cost),
+                format!("{:.0} %", graph.cost_percent(node) * 100.),
+            ]
+        })
+        .collect::<Vec<_>>();
+    section(
+        &mut output,
+        &format!("Top {TOP} nodes by cost"),
+        &["Node", "Cost", "%"],
+        &rows,
+    );
+
+    let rows = graph
+        .nodes
+        .iter()
+        .filter_map(|node| {
+            let factor = node.misestimate().filter(|x| *x >= MISESTIMATE)?;
+
+            Some(vec![
+                name(node),
+                node.rows.to_string(),
+                node.actual_rows()?.to_string(),
+                format!("×{factor:.0}"),
+            ])
+        })
+        .collect::<Vec<_>>();
+    section(
+        &mut output,
+        "Misestimated rows",
+        &["Node", "Estimated", "Actual", "Factor"],
+        &rows,
+    );
+
+    let rows = graph
+        .nodes
+        .iter()
+        .filter_map(|node| Some(vec![name(node), node.spill()?]))
+        .collect::<Vec<_>>();
+    section(&mut output, "Spills to disk", &["Node", "Detail"], &rows);
+
+    let rows = graph
+        .nodes
+        .iter()
+        .filter(|node| node.ty == "Seq Scan" && node.scanned_rows() >= BIG_RELATION)
+        .map(|node| vec![name(node), format!("{:.0}", node.scanned_rows())])
+        .collect::<Vec<_>>();
+    section(
+        &mut output,
+        "Sequential scans on big relations",
+        &["Node", "Rows read"],
+        &rows,
+    );
+
+    output.push_str("\n<details>\n<summary>Plan</summary>\n\n```\n");
+    for (n, node) in graph.nodes.iter().enumerate() {
+        let depth = graph.depth(n);
+        let indent = if depth == 0 {
+            String::new()
+        } else {
+            format!("{}-> ", "   ".repeat(depth))
+        };
+
+        write!(
+            output,
+            "{indent}{} (cost={:.2} rows={}",
+            node.title(),
+            node.cost,
+            node.rows
+        )
+        .ok();
+        if !node.executed {
+            output.push_str(" never executed");
+        } else if let Some(time) = node.time {
+            write!(output, " time={time:.2} ms").ok();
+        }
+        output.push_str(")\n");
+    }
+    output.push_str("```\n\n</details>\n");
+
+    output
+}
+
+fn section(output: &mut String, title: &str, headers: &[&str], rows: &[Vec<String>]) {
+    if rows.is_empty() {
+        return;
+    }
+
+    writeln!(output, "\n### {title}\n").ok();
+    writeln!(output, "| {} |", headers.join(" | ")).ok();
+    writeln!(
+        output,
+        "|{}|",
+        headers.iter().map(|_| "---").collect::<Vec<_>>().join("|")
+    )
+    .ok();
+
+    for row in rows {
+        writeln!(output, "| {} |", row.join(" | ")).ok();
+    }
+}
+
+fn name(node: &super::Node) -> String {
+    format!("#{} {}", node.id, node.title())
+        .replace('|', "\\|")
+        .replace('`', "'")
+}
+
+fn duration(time: Option<f32>) -> String {
+    time.map_or_else(|| "-".to_string(), |x| format!("{x:.2} ms"))
+}
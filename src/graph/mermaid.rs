@@ -0,0 +1,99 @@
+use dot2::GraphWalk as _;
+use std::fmt::Write as _;
+
+pub(super) fn render(graph: &super::Graph) -> String {
+    let mut output = "flowchart TB\n".to_string();
+    let mut classes = std::collections::BTreeMap::new();
+
+    let subgraphs = graph.subgraphs();
+    for (n, node) in graph.nodes.iter().enumerate() {
+        if node.subplan.is_none() {
+            writeln!(output, "    {}", self::node(graph, n, node)).ok();
+        }
+    }
+
+    for (k, s) in subgraphs.iter().enumerate() {
+        writeln!(output, "    subgraph cluster{k}[\"<b>{}</b>\"]", escape(s)).ok();
+        for n in graph.subgraph_nodes(s).iter() {
+            writeln!(
+                output,
+                "        {}",
+                self::node(graph, *n, &graph.nodes[*n])
+            )
+            .ok();
+        }
+        output.push_str("    end\n");
+    }
+
+    for (source, target) in &graph.edges {
+        writeln!(output, "    node{source} --- node{target}").ok();
+    }
+
+    for (n, node) in graph.nodes.iter().enumerate() {
+        for (name, style) in self::classes(graph, node) {
+            writeln!(output, "    class node{n} {name}").ok();
+            classes.insert(name, style);
+        }
+    }
+
+    for (name, style) in classes {
+        writeln!(output, "    classDef {name} {style}").ok();
+    }
+
+    output
+}
+
+fn node(graph: &super::Graph, n: usize, node: &super::Node) -> String {
+    let mut label = format!("<b>{}</b>", escape(&node.ty));
+
+    if let Some(time) = graph.time_label(node) {
+        write!(label, " | {}", escape(&time)).ok();
+    }
+    if !node.info.is_empty() {
+        write!(label, "<br/>{}", escape(&node.info)).ok();
+    }
+    if !node.workers.is_empty() {
+        write!(label, "<br/>Workers: {}", node.workers.len()).ok();
+    }
+    write!(
+        label,
+        "<br/>Cost: {:.02}<br/>Rows: {}",
+        node.cost, node.rows
+    )
+    .ok();
+
+    format!("node{n}[\"{label}\"]")
+}
+
+fn classes(graph: &super::Graph, node: &super::Node) -> Vec<(String, String)> {
+    let mut classes = Vec::new();
+
+    let heat = (graph.cost_percent(node) * 10.).round();
+    if heat >= 1. {
+        let color = super::Graph::color(heat / 10.);
+
+        classes.push((format!("heat{heat}"), format!("fill:{color}")));
+    }
+
+    if !node.executed {
+        classes.push(("never".to_string(), "stroke:gray,color:gray".to_string()));
+    } else if let Some(time) = node.time {
+        let percent = graph.time_percent(time);
+        let color = super::Graph::duration_color(percent);
+
+        if time >= 1. && color != "white" {
+            let name = format!("duration{}", color.trim_start_matches('#'));
+
+            classes.push((name, format!("stroke:{color},stroke-width:4px")));
+        }
+    }
+
+    classes
+}
+
+fn escape(s: &str) -> String {
+    s.replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
@@ -0,0 +1,33 @@
+use std::fmt::Write as _;
+
+pub(super) fn render(graph: &super::Graph) -> String {
+    let mut output = "@startwbs\n".to_string();
+
+    for (n, node) in graph.nodes.iter().enumerate() {
+        let stars = "*".repeat(graph.depth(n) + 1);
+        let color = if node.executed {
+            graph
+                .fill_color(node)
+                .map(|color| format!("[{color}]"))
+                .unwrap_or_default()
+        } else {
+            "[#lightgrey]".to_string()
+        };
+
+        let mut lines = graph.label_lines(node);
+        lines[0] = format!("<b>{}</b>", escape(&lines[0]));
+        for line in lines.iter_mut().skip(1) {
+            *line = escape(line);
+        }
+
+        writeln!(output, "{stars}{color}:{};", lines.join("\n")).ok();
+    }
+
+    output.push_str("@endwbs\n");
+
+    output
+}
+
+fn escape(s: &str) -> String {
+    s.replace('<', "~<").trim_end_matches(';').to_string()
+}
@@ -0,0 +1,273 @@
+use super::{Accuracy, Graph};
+
+const FIXTURES: [(&str, &str); 8] = [
+    ("plan_1", include_str!("../../examples/plan_1.json")),
+    ("plan_2", include_str!("../../examples/plan_2.json")),
+    ("plan_3", include_str!("../../examples/plan_3.json")),
+    ("plan_4", include_str!("../../examples/plan_4.json")),
+    ("plan_5", include_str!("../../examples/plan_5.json")),
+    ("plan_large", include_str!("../../examples/plan_large.json")),
+    (
+        "plan_parallel",
+        include_str!("../../examples/plan_parallel.json"),
+    ),
+    (
+        "plan_trigger",
+        include_str!("../../examples/plan_trigger.json"),
+    ),
+];
+
+fn graph(name: &str) -> Graph {
+    let (_, json) = FIXTURES.iter().find(|(x, _)| *x == name).unwrap();
+    let explains: Vec<crate::Explain> = serde_json::from_str(json).unwrap();
+
+    Graph::from(&explains[0])
+}
+
+fn time(graph: &Graph, n: usize) -> f32 {
+    graph.nodes[n].time.unwrap()
+}
+
+#[test]
+fn exclusive_time_positive() {
+    for (name, _) in FIXTURES {
+        let graph = graph(name);
+
+        for node in &graph.nodes {
+            assert!(node.time.unwrap() >= 0., "{name} #{}", node.id);
+        }
+    }
+}
+
+#[test]
+fn exclusive_times_add_up() {
+    for (name, _) in FIXTURES {
+        let graph = graph(name);
+
+        if graph.nodes.iter().any(|x| x.accuracy == Accuracy::Clamped) {
+            continue;
+        }
+
+        let total = graph.nodes.iter().map(|x| x.time.unwrap()).sum::<f32>();
+        let root = graph.nodes[0].inclusive_time.unwrap();
+
+        // Each value is rounded to the µs.
+        let tolerance = 0.001 * graph.nodes.len() as f32;
+        assert!(
+            (total - root).abs() <= tolerance,
+            "{name}: {total} != {root}"
+        );
+    }
+}
+
+#[test]
+fn parallel() {
+    let graph = graph("plan_parallel");
+
+    // The finalize aggregate ends before the gather shutdown.
+    assert_eq!(time(&graph, 0), 0.);
+    assert_eq!(graph.nodes[0].accuracy, Accuracy::Clamped);
+
+    // The participants run concurrently: times aren't multiplied by loops.
+    assert!((time(&graph, 1) - (486.428 - 482.39)).abs() < 0.001);
+    assert_eq!(graph.nodes[3].participants, 3);
+    assert!((time(&graph, 3) - 344.447).abs() < 0.001);
+    assert_eq!(graph.nodes[3].accuracy, Accuracy::Exact);
+}
+
+#[test]
+fn cte_scan() {
+    let graph = graph("plan_4");
+
+    // The CTE variants_min_prices is executed by its only scan.
+    let root = graph
+        .nodes
+        .iter()
+        .position(|x| x.subplan.as_deref() == Some("CTE variants_min_prices"))
+        .unwrap();
+    assert_eq!(graph.consumers(root), [37]);
+
+    let scan = &graph.nodes[37];
+    let cte = graph.nodes[root].inclusive_time.unwrap();
+    assert!((scan.time.unwrap() - (scan.inclusive_time.unwrap() - cte)).abs() < 0.001);
+    assert_eq!(scan.accuracy, Accuracy::Exact);
+
+    // The CTE catalog_variants is shared between its scans.
+    assert!(graph.consumers(1).len() > 1);
+    assert!(
+        graph
+            .consumers(1)
+            .iter()
+            .all(|x| graph.nodes[*x].accuracy == Accuracy::Estimated)
+    );
+}
+
+#[test]
+fn rounding() {
+    let graph = graph("plan_large");
+
+    // 70445 loops of an average rounded to the µs.
+    assert_eq!(graph.nodes[69].loops, Some(70445));
+    assert_eq!(graph.nodes[69].accuracy, Accuracy::Estimated);
+}
+
+#[test]
+fn never_executed() {
+    let graph = graph("plan_1");
+
+    assert!(!graph.nodes[3].executed);
+    assert_eq!(time(&graph, 3), 0.);
+}
+
+#[test]
+fn critical_path_follows_time() {
+    for name in ["plan_4", "plan_large"] {
+        let graph = graph(name);
+        let path_time = |path: &[usize]| path.iter().map(|x| time(&graph, *x).max(0.)).sum::<f32>();
+
+        let critical = path_time(&graph.critical_path);
+        let slowest = (0..graph.nodes.len())
+            .filter(|n| graph.children(*n).next().is_none())
+            .map(|mut n| {
+                let mut path = vec![n];
+                while let Some(parent) = graph.parent(n) {
+                    path.push(parent);
+                    n = parent;
+                }
+                path_time(&path)
+            })
+            .fold(0., f32::max);
+
+        assert!(
+            (critical - slowest).abs() < 0.001,
+            "{name}: {critical} != {slowest}"
+        );
+    }
+}
+
+#[test]
+fn siblings_using_other_indexes() {
+    let json = r#"[{"Plan": {
+        "Node Type": "BitmapOr", "Startup Cost": 0, "Total Cost": 2, "Plan Rows": 20, "Plan Width": 0,
+        "Plans": [
+            {"Node Type": "Bitmap Index Scan", "Parent Relationship": "Member", "Index Name": "t_a_idx",
+             "Startup Cost": 0, "Total Cost": 1, "Plan Rows": 10, "Plan Width": 0},
+            {"Node Type": "Bitmap Index Scan", "Parent Relationship": "Member", "Index Name": "t_b_idx",
+             "Startup Cost": 0, "Total Cost": 1, "Plan Rows": 10, "Plan Width": 0}
+        ]
+    }}]"#;
+    let explains: Vec<crate::Explain> = serde_json::from_str(json).unwrap();
+
+    let mut graph = Graph::from(&explains[0]);
+    graph.group_siblings();
+    assert!(graph.nodes.iter().all(|x| !x.hidden));
+
+    // Two index scans of the same table, with different indexes.
+    let json = json
+        .replace("BitmapOr", "Nested Loop\", \"Join Type\": \"Inner")
+        .replace(
+            "Bitmap Index Scan\"",
+            "Index Scan\", \"Relation Name\": \"t\", \"Alias\": \"t\"",
+        );
+    let explains: Vec<crate::Explain> = serde_json::from_str(&json).unwrap();
+
+    let mut graph = Graph::from(&explains[0]);
+    graph.group_siblings();
+    assert!(graph.nodes.iter().all(|x| !x.hidden));
+}
+
+#[test]
+fn truncated_info() {
+    // The 80th byte is inside the é.
+    let call = format!("unnest('{{{}é,b}}'::text[])", "a".repeat(70));
+    let json = serde_json::json!([{"Plan": {
+        "Node Type": "Function Scan", "Function Call": call, "Alias": "unnest",
+        "Startup Cost": 0, "Total Cost": 1, "Plan Rows": 2, "Plan Width": 32
+    }}]);
+    let explains: Vec<crate::Explain> = serde_json::from_value(json).unwrap();
+
+    let graph = Graph::from(&explains[0]);
+    assert!(graph.nodes[0].info.ends_with("a…"));
+}
+
+/// An Append over `count` partitions of `orders`, scanned in 1 ms each.
+fn partitions(count: usize) -> Graph {
+    let plans = (1..=count)
+        .map(|n| {
+            serde_json::json!({
+                "Node Type": "Seq Scan", "Parent Relationship": "Member",
+                "Relation Name": format!("orders_{n}"), "Alias": format!("orders_{n}"),
+                "Startup Cost": 0, "Total Cost": 10, "Plan Rows": 100, "Plan Width": 4,
+                "Actual Startup Time": 0, "Actual Total Time": 1, "Actual Rows": 100, "Actual Loops": 1
+            })
+        })
+        .collect::<Vec<_>>();
+    let json = serde_json::json!([{
+        "Plan": {
+            "Node Type": "Append", "Startup Cost": 0, "Total Cost": 10 * count, "Plan Rows": 100 * count,
+            "Plan Width": 4, "Actual Startup Time": 0, "Actual Total Time": count + 1,
+            "Actual Rows": 100 * count, "Actual Loops": 1, "Plans": plans
+        },
+        "Execution Time": count + 1,
+    }]);
+    let explains: Vec<crate::Explain> = serde_json::from_value(json).unwrap();
+
+    Graph::from(&explains[0])
+}
+
+fn filtered(mut graph: Graph, options: super::Options) -> Vec<usize> {
+    graph.options = options;
+    graph.group_siblings();
+    graph.filter().unwrap();
+
+    (0..graph.nodes.len())
+        .filter(|n| graph.is_visible(*n))
+        .collect()
+}
+
+#[test]
+fn focus_grouped_node() {
+    for focus in ["orders_5", "5"] {
+        let options = super::Options {
+            collapse: true,
+            focus: Some(focus.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(filtered(partitions(20), options), [0, 1], "{focus}");
+    }
+}
+
+#[test]
+fn min_time_percent_of_group() {
+    // Each partition takes less than 5 %, all of them more.
+    let options = super::Options {
+        collapse: true,
+        min_time_percent: Some(5.),
+        ..Default::default()
+    };
+
+    assert_eq!(filtered(partitions(20), options), [0, 1]);
+}
+
+#[test]
+fn escaped_info() {
+    let json = serde_json::json!([{"Plan": {
+        "Node Type": "Function Scan", "Function Call": "jsonb_path_query(doc, '$.a ? (@ < 3 && @ > 1)')",
+        "Alias": "jsonb_path_query", "Startup Cost": 0, "Total Cost": 1, "Plan Rows": 2, "Plan Width": 32
+    }}]);
+    let explains: Vec<crate::Explain> = serde_json::from_value(json).unwrap();
+
+    let dot = Graph::from(&explains[0]).render();
+    assert!(dot.contains("(@ &lt; 3 &amp;&amp; @ &gt; 1)"));
+}
+
+#[test]
+fn json_decimals() {
+    let json: serde_json::Value =
+        serde_json::from_str(&super::json::render(&graph("plan_1"))).unwrap();
+
+    assert_eq!(json["execution_time"], 0.134);
+    assert_eq!(json["plan"]["actual_total_time"], 0.007);
+    assert_eq!(json["plan"]["plans"][0]["actual_total_time"], 0.006);
+}
@@ -0,0 +1,167 @@
+use std::fmt::Write as _;
+
+const WIDTH: f32 = 1200.;
+const LABEL_WIDTH: f32 = 400.;
+const ROW_HEIGHT: f32 = 20.;
+const TICKS: usize = 5;
+const LABEL_LEN: usize = 60;
+
+struct Row {
+    label: String,
+    depth: usize,
+    bar: Option<Bar>,
+}
+
+struct Bar {
+    startup_time: f32,
+    total_time: f32,
+    loops: usize,
+    color: &'static str,
+}
+
+pub(super) fn render(graph: &super::Graph) -> String {
+    let mut sections = vec![("Main".to_string(), main_rows(graph))];
+
+    for number in graph.worker_numbers() {
+        sections.push((format!("Worker {number}"), worker_rows(graph, number)));
+    }
+
+    let max_time = sections
+        .iter()
+        .flat_map(|(_, rows)| rows.iter().filter_map(|row| row.bar.as_ref()))
+        .map(|bar| bar.total_time)
+        .fold(0., f32::max);
+    let scale = if max_time > 0. {
+        (WIDTH - LABEL_WIDTH - 20.) / max_time
+    } else {
+        0.
+    };
+
+    let n_rows = sections
+        .iter()
+        .map(|(_, rows)| rows.len() + 1)
+        .sum::<usize>();
+    let height = (n_rows + 2) as f32 * ROW_HEIGHT;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" font-family="sans-serif" font-size="12">"#
+    );
+    svg.push_str(r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    for tick in 0..=TICKS {
+        let time = max_time * tick as f32 / TICKS as f32;
+        let x = LABEL_WIDTH + time * scale;
+
+        write!(
+            svg,
+            r#"<line x1="{x}" y1="{ROW_HEIGHT}" x2="{x}" y2="{height}" stroke="lightgrey"/><text x="{x}" y="{}" text-anchor="middle">{time:.2} ms</text>"#,
+            ROW_HEIGHT - 5.
+        )
+        .ok();
+    }
+
+    let mut y = ROW_HEIGHT * 2.;
+
+    for (name, rows) in &sections {
+        write!(
+            svg,
+            r#"<text x="5" y="{}" font-weight="bold">{name}</text>"#,
+            y - 5.
+        )
+        .ok();
+        y += ROW_HEIGHT;
+
+        for row in rows {
+            let x = 5. + row.depth as f32 * 10.;
+            let label = if row.label.chars().count() > LABEL_LEN {
+                format!("{}…", row.label.chars().take(LABEL_LEN).collect::<String>())
+            } else {
+                row.label.clone()
+            };
+            let label = super::xml_escape(&label);
+
+            if let Some(bar) = &row.bar {
+                write!(svg, r#"<text x="{x}" y="{}">{label}</text>"#, y - 5.).ok();
+                write!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="gray"><title>startup: {:.2} ms, total: {:.2} ms, loops: {}</title></rect>"#,
+                    LABEL_WIDTH + bar.startup_time * scale,
+                    y - ROW_HEIGHT + 3.,
+                    ((bar.total_time - bar.startup_time) * scale).max(1.),
+                    ROW_HEIGHT - 6.,
+                    bar.color,
+                    bar.startup_time,
+                    bar.total_time,
+                    bar.loops,
+                )
+                .ok();
+            } else {
+                write!(
+                    svg,
+                    r#"<text x="{x}" y="{}" fill="gray">{label}</text>"#,
+                    y - 5.
+                )
+                .ok();
+            }
+
+            y += ROW_HEIGHT;
+        }
+    }
+
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+fn main_rows(graph: &super::Graph) -> Vec<Row> {
+    graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(n, node)| {
+            let bar = match (node.executed, node.startup_time, node.total_time) {
+                (true, Some(startup_time), Some(total_time)) => Some(Bar {
+                    startup_time,
+                    total_time,
+                    loops: node.loops.unwrap_or(1),
+                    color: color(graph, node),
+                }),
+                _ => None,
+            };
+
+            Row {
+                label: node.title(),
+                depth: graph.depth(n),
+                bar,
+            }
+        })
+        .collect()
+}
+
+fn worker_rows(graph: &super::Graph, number: usize) -> Vec<Row> {
+    graph
+        .nodes
+        .iter()
+        .enumerate()
+        .filter_map(|(n, node)| {
+            let worker = node.workers.iter().find(|x| x.number == number)?;
+
+            Some(Row {
+                label: node.title(),
+                depth: graph.depth(n),
+                bar: Some(Bar {
+                    startup_time: worker.actual_startup_time,
+                    total_time: worker.actual_total_time,
+                    loops: worker.actual_loops,
+                    color: color(graph, node),
+                }),
+            })
+        })
+        .collect()
+}
+
+fn color(graph: &super::Graph, node: &super::Node) -> &'static str {
+    node.time.map_or("white", |time| {
+        super::Graph::duration_color(graph.time_percent(time))
+    })
+}
@@ -0,0 +1,206 @@
+use serde_json::json;
+
+/// A node execution laid out on a thread timeline, in milliseconds.
+struct Span {
+    node: usize,
+    start: f64,
+    end: f64,
+}
+
+pub(super) fn speedscope(graph: &super::Graph) -> String {
+    let frames = graph
+        .nodes
+        .iter()
+        .map(|node| json!({ "name": node.title() }))
+        .collect::<Vec<_>>();
+
+    let profiles = threads(graph)
+        .iter()
+        .map(|(thread, spans)| {
+            let mut events = Vec::new();
+            let mut stack: Vec<&Span> = Vec::new();
+
+            for span in spans {
+                while let Some(top) = stack.pop_if(|top| top.end <= span.start) {
+                    events.push(json!({ "type": "C", "frame": top.node, "at": top.end }));
+                }
+
+                events.push(json!({ "type": "O", "frame": span.node, "at": span.start }));
+                stack.push(span);
+            }
+
+            while let Some(top) = stack.pop() {
+                events.push(json!({ "type": "C", "frame": top.node, "at": top.end }));
+            }
+
+            json!({
+                "type": "evented",
+                "name": thread_name(*thread),
+                "unit": "milliseconds",
+                "startValue": 0,
+                "endValue": spans.iter().map(|x| x.end).fold(0., f64::max),
+                "events": events,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "https://www.speedscope.app/file-format-schema.json",
+        "exporter": concat!("explain ", env!("CARGO_PKG_VERSION")),
+        "name": "explain",
+        "activeProfileIndex": 0,
+        "shared": { "frames": frames },
+        "profiles": profiles,
+    })
+    .to_string()
+}
+
+pub(super) fn chrome(graph: &super::Graph) -> String {
+    let mut events = Vec::new();
+
+    for (thread, spans) in threads(graph) {
+        let tid = thread.map_or(0, |number| number + 1);
+
+        events.push(json!({
+            "name": "thread_name",
+            "ph": "M",
+            "pid": 1,
+            "tid": tid,
+            "args": { "name": thread_name(thread) },
+        }));
+
+        for span in spans {
+            let node = &graph.nodes[span.node];
+
+            events.push(json!({
+                "name": node.title(),
+                "cat": node.ty,
+                "ph": "X",
+                "ts": span.start * 1_000.,
+                "dur": (span.end - span.start) * 1_000.,
+                "pid": 1,
+                "tid": tid,
+                "args": args(node, thread),
+            }));
+        }
+    }
+
+    json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+    })
+    .to_string()
+}
+
+/// The main process timeline, then one timeline per parallel worker.
+fn threads(graph: &super::Graph) -> Vec<(Option<usize>, Vec<Span>)> {
+    std::iter::once(None)
+        .chain(graph.worker_numbers().into_iter().map(Some))
+        .map(|thread| {
+            let mut spans = Vec::new();
+
+            if !graph.nodes.is_empty() {
+                layout(graph, 0, thread, 0., f64::INFINITY, &mut spans);
+            }
+
+            (thread, spans)
+        })
+        .filter(|(_, spans)| !spans.is_empty())
+        .collect()
+}
+
+/// A child span starts its startup time after its parent one, since the time
+/// to the first row is spent before the parent gets it. Spans are clamped to
+/// their parent and after their previous sibling, so they stay nested like a
+/// flame chart.
+fn layout(
+    graph: &super::Graph,
+    n: usize,
+    thread: Option<usize>,
+    start: f64,
+    limit: f64,
+    spans: &mut Vec<Span>,
+) -> f64 {
+    let (end, limit) = match duration(&graph.nodes[n], thread) {
+        Some(duration) => {
+            let end = (start + duration).min(limit);
+
+            if end > start {
+                spans.push(Span {
+                    node: n,
+                    start,
+                    end,
+                });
+            }
+
+            (Some(end), end)
+        }
+        None => (None, limit),
+    };
+
+    let mut cursor = start;
+
+    for child in graph.children(n) {
+        let startup = startup(&graph.nodes[child], thread).unwrap_or_default();
+        let child_start = (start + startup).max(cursor).min(limit);
+
+        cursor = layout(graph, child, thread, child_start, limit, spans);
+    }
+
+    end.unwrap_or(cursor)
+}
+
+fn startup(node: &super::Node, thread: Option<usize>) -> Option<f64> {
+    let startup = match thread {
+        None => node.startup_time?,
+        Some(number) => {
+            node.workers
+                .iter()
+                .find(|x| x.number == number)?
+                .actual_startup_time
+        }
+    };
+
+    Some(f64::from(startup))
+}
+
+fn duration(node: &super::Node, thread: Option<usize>) -> Option<f64> {
+    let duration = match thread {
+        None => f64::from(node.inclusive_time?),
+        Some(number) => {
+            let worker = node.workers.iter().find(|x| x.number == number)?;
+
+            f64::from(worker.actual_total_time) * worker.actual_loops as f64
+        }
+    };
+
+    Some(duration).filter(|x| *x > 0.)
+}
+
+fn args(node: &super::Node, thread: Option<usize>) -> serde_json::Value {
+    match thread {
+        None => json!({
+            "startup_time": node.startup_time.map(super::decimal),
+            "total_time": node.total_time.map(super::decimal),
+            "loops": node.loops,
+            "plan_rows": node.rows,
+        }),
+        Some(number) => {
+            let worker = node.workers.iter().find(|x| x.number == number);
+
+            json!({
+                "startup_time": worker.map(|x| super::decimal(x.actual_startup_time)),
+                "total_time": worker.map(|x| super::decimal(x.actual_total_time)),
+                "loops": worker.map(|x| x.actual_loops),
+                "rows": worker.map(|x| x.actual_rows),
+            })
+        }
+    }
+}
+
+fn thread_name(thread: Option<usize>) -> String {
+    match thread {
+        None => "Main".to_string(),
+        Some(number) => format!("Worker {number}"),
+    }
+}
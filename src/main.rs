@@ -1,5 +1,6 @@
 #![warn(warnings)]
 
+mod check;
 mod errors;
 mod explain;
 mod graph;
@@ -18,6 +19,9 @@ struct Opt {
     /// Specifies the command to execute
     #[arg(short, long)]
     command: Option<String>,
+    /// Only show the critical path in the graph, collapsing the other nodes
+    #[arg(long)]
+    critical_path: bool,
     /// Specifies the name of the database to connect to
     #[arg(short, long, add = clap_complete::engine::ArgValueCompleter::new(complete_dbname))]
     dbname: Option<String>,
@@ -27,13 +31,32 @@ struct Opt {
     /// Read commands from the file, rather than standard input
     #[arg(short, long, value_hint = clap::ValueHint::FilePath)]
     file: Option<String>,
-    #[arg(long, action = clap::ArgAction::Help)]
+    /// Only show the subtree of the node with this id or relation, and its
+    /// ancestors
+    #[arg(long)]
+    focus: Option<String>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Dot)]
+    format: Format,
+    #[arg(long, action = clap::ArgAction::Help, global = true)]
     help: Option<bool>,
     /// Specifies the host name of the machine on which the server is running
     #[arg(short, long, value_hint = clap::ValueHint::Hostname)]
     host: Option<String>,
+    /// Show the warnings about the plan, rather than the graph
+    #[arg(long)]
+    lint: bool,
+    /// Summarize the nodes deeper than this level
+    #[arg(long)]
+    max_depth: Option<usize>,
+    /// Hide the nodes taking less than this percentage of the execution time
+    #[arg(long)]
+    min_time_percent: Option<f32>,
+    /// Draw every sibling subtree, rather than grouping the identical ones
+    #[arg(long)]
+    no_collapse: bool,
     /// Put output into file
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     output: Option<String>,
     /// Prompt for a password before connecting to a database
     #[arg(short = 'W', long)]
@@ -41,9 +64,92 @@ struct Opt {
     /// Specifies the TCP port on which the server is listening for connections
     #[arg(short, long)]
     port: Option<String>,
+    /// List the output expressions of each node in the graph
+    #[arg(long)]
+    show_output: bool,
     /// Connect to the database as the user
     #[arg(short = 'U', long, value_hint = clap::ValueHint::Username)]
     user: Option<String>,
+    #[command(subcommand)]
+    action: Option<Action>,
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+enum Action {
+    /// Compare the plans of stored queries to their baseline
+    Check(CheckOpt),
+    /// Compare two explain plans in JSON of the same query
+    Diff(DiffOpt),
+}
+
+#[derive(Clone, Debug, clap::Args)]
+struct CheckOpt {
+    /// Directory of the queries (name.sql), their baseline plans (name.json)
+    /// and the optional rules.json
+    #[arg(long, value_hint = clap::ValueHint::DirPath)]
+    baseline: String,
+    /// Read the new plans in JSON from this directory, rather than explaining
+    /// the queries
+    #[arg(long, value_hint = clap::ValueHint::DirPath)]
+    plans: Option<String>,
+    /// Replace the baseline plans by the new ones
+    #[arg(long)]
+    update: bool,
+}
+
+#[derive(Clone, Debug, clap::Args)]
+struct DiffOpt {
+    /// Plan before the change
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    before: String,
+    /// Plan after the change
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    after: String,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = DiffFormat::Dot)]
+    format: DiffFormat,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum DiffFormat {
+    /// Graphviz graph
+    Dot,
+    /// Summary of the changes
+    Markdown,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum Format {
+    /// Graphviz graph
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+    /// Folded stacks, for inferno or flamegraph.pl
+    Folded,
+    /// Flamegraph SVG
+    Flamegraph,
+    /// Speedscope profile
+    Speedscope,
+    /// Chrome trace event JSON
+    Chrome,
+    /// Node startup and completion timeline SVG
+    Timeline,
+    /// Plan tree with computed metrics, see docs/JSON.md
+    Json,
+    /// Per node metrics, comma separated
+    Csv,
+    /// Per node metrics, tab separated
+    Tsv,
+    /// Summary report for code review comments
+    Markdown,
+    /// PlantUML work breakdown structure
+    Plantuml,
+    /// draw.io diagram
+    Drawio,
+    /// GraphML
+    Graphml,
+    /// Cytoscape.js elements JSON
+    Cytoscape,
 }
 
 fn complete_dbname(current: &std::ffi::OsStr) -> Vec<clap_complete::CompletionCandidate> {
@@ -91,6 +197,51 @@ fn main() -> Result {
 
     let opt = Opt::parse();
 
+    match &opt.action {
+        Some(Action::Check(check)) => {
+            let report = check::run(&opt, check)?;
+
+            write(opt.output.as_deref(), &report.to_string())?;
+
+            if !report.is_success() {
+                std::process::exit(1);
+            }
+
+            return Ok(());
+        }
+        Some(Action::Diff(diff)) => {
+            let before = load(&diff.before)?;
+            let after = load(&diff.after)?;
+
+            let output = match diff.format {
+                DiffFormat::Dot => graph::diff_dot(&before[0], &after[0]),
+                DiffFormat::Markdown => graph::diff_markdown(&before[0], &after[0]),
+            };
+
+            return write(opt.output.as_deref(), &output);
+        }
+        None => (),
+    }
+
+    if opt.lint && !matches!(opt.format, Format::Dot) {
+        return Err(Error::LintFormat);
+    }
+
+    if opt.lint || !matches!(opt.format, Format::Dot) {
+        let dot_only = [
+            ("--critical-path", opt.critical_path),
+            ("--focus", opt.focus.is_some()),
+            ("--max-depth", opt.max_depth.is_some()),
+            ("--min-time-percent", opt.min_time_percent.is_some()),
+            ("--no-collapse", opt.no_collapse),
+            ("--show-output", opt.show_output),
+        ];
+
+        if let Some((name, _)) = dot_only.iter().find(|(_, set)| *set) {
+            return Err(Error::DotOnly(name));
+        }
+    }
+
     let query = match (&opt.command, &opt.file) {
         (Some(query), None) => query.clone(),
         (None, Some(file)) => std::fs::read_to_string(file)?,
@@ -107,26 +258,68 @@ fn main() -> Result {
     let json = if opt.dry_run {
         serde_json::from_str(&query)?
     } else {
-        let analyse = if opt.analyse { ", analyse" } else { "" };
-        let explain_query =
-            format!("explain (format json, costs, verbose, summary{analyse}) {query}");
-
         let client = try_connect(&opt)?;
 
-        let results = client.execute(explain_query.as_str())?;
-        results.get(0).get("\"QUERY PLAN\"")
+        explain(&client, &query, opt.analyse)?
     };
 
     let explains: Vec<Explain> = serde_json::from_value(json)?;
-    let graph = graph::dot(&explains[0]);
+    let options = graph::Options {
+        collapse: !opt.no_collapse,
+        critical_path: opt.critical_path,
+        focus: opt.focus.clone(),
+        max_depth: opt.max_depth,
+        min_time_percent: opt.min_time_percent,
+        show_output: opt.show_output,
+    };
+    let graph = if opt.lint {
+        graph::lint(&explains[0])
+    } else {
+        match opt.format {
+            Format::Dot => graph::dot(&explains[0], &options)?,
+            Format::Mermaid => graph::mermaid(&explains[0]),
+            Format::Folded => graph::folded(&explains[0]),
+            Format::Flamegraph => graph::flamegraph(&explains[0])?,
+            Format::Speedscope => graph::speedscope(&explains[0]),
+            Format::Chrome => graph::chrome(&explains[0]),
+            Format::Timeline => graph::timeline(&explains[0]),
+            Format::Json => graph::json(&explains[0]),
+            Format::Csv => graph::csv(&explains[0]),
+            Format::Tsv => graph::tsv(&explains[0]),
+            Format::Markdown => graph::markdown(&explains[0]),
+            Format::Plantuml => graph::plantuml(&explains[0]),
+            Format::Drawio => graph::drawio(&explains[0]),
+            Format::Graphml => graph::graphml(&explains[0]),
+            Format::Cytoscape => graph::cytoscape(&explains[0]),
+        }
+    };
+
+    write(opt.output.as_deref(), &graph)
+}
+
+fn explain(client: &elephantry::Pool, query: &str, analyse: bool) -> Result<serde_json::Value> {
+    let analyse = if analyse { ", analyse" } else { "" };
+    let explain_query = format!("explain (format json, costs, verbose, summary{analyse}) {query}");
+
+    let results = client.execute(explain_query.as_str())?;
+
+    Ok(results.get(0).get("\"QUERY PLAN\""))
+}
+
+fn load(file: &str) -> Result<Vec<Explain>> {
+    let json = std::fs::read_to_string(file)?;
+
+    Ok(serde_json::from_str(&json)?)
+}
 
-    if let Some(output) = opt.output {
+fn write(output: Option<&str>, content: &str) -> Result {
+    if let Some(output) = output {
         use std::io::Write;
 
         let mut output = std::fs::File::create(output)?;
-        output.write_all(graph.as_bytes())?;
+        output.write_all(content.as_bytes())?;
     } else {
-        print!("{graph}");
+        print!("{content}");
     }
 
     Ok(())